@@ -0,0 +1,454 @@
+use std::fmt;
+
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset},
+    prelude::Vec2,
+    reflect::TypeUuid,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::{
+        obstacle::Blueprint,
+        paddle::{Role, Side},
+    },
+    constants::{
+        BALL_DEFAULT_STARTING_POSITION, BOTTOM_WALL_POSITION, BOTTOM_WALL_SIZE, COUNTDOWN_POSITION,
+        LEFT_PADDLE_STARTING_POSITION, LEFT_SCORE_POSITION, RIGHT_PADDLE_STARTING_POSITION,
+        RIGHT_SCORE_POSITION, TOP_WALL_POSITION, TOP_WALL_SIZE,
+    },
+};
+
+/// The file extension arena assets are loaded from.
+pub const EXTENSION: &str = "arena.ron";
+
+/// A rectangle in the arena, given by its centre and its width and height.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Area {
+    /// The centre of the rectangle.
+    pub position: Vec2,
+    /// The width and height of the rectangle.
+    pub size: Vec2,
+}
+
+/// A rectangle in the arena that belongs to one of the players.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SideArea {
+    /// The player the rectangle belongs to.
+    pub side: Side,
+    /// For paddle zones, which of the side's paddles it belongs to.
+    #[serde(default)]
+    pub role: Role,
+    /// The centre of the rectangle.
+    pub position: Vec2,
+    /// The width and height of the rectangle.
+    pub size: Vec2,
+}
+
+/// A point in the arena that belongs to one of the players.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SidePoint {
+    /// The player the point belongs to.
+    pub side: Side,
+    /// For paddles, where the paddle plays in its team.
+    #[serde(default)]
+    pub role: Role,
+    /// Where the point is.
+    pub position: Vec2,
+}
+
+/// The layout of a court: where its walls and goals are, where each player
+/// may move, and where everything starts.
+///
+/// Arenas are loaded from `.arena.ron` files in the `arenas` asset folder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeUuid)]
+#[uuid = "6a4f5d0e-3c1b-4a8e-9f2d-7b5e1c9a0d34"]
+pub struct Arena {
+    /// The name shown in the main menu.
+    pub name: String,
+    /// The walls the ball bounces off.
+    pub walls: Vec<Area>,
+    /// The zones a player has to get the ball into to score. The zone's side
+    /// is the player defending it.
+    pub goals: Vec<SideArea>,
+    /// The zones each player's paddle is kept inside. A side with a defender
+    /// and an attacker needs a zone for each.
+    pub paddle_zones: Vec<SideArea>,
+    /// Where each player's paddle starts. A side can have a defender and an
+    /// attacker, who play as a team.
+    pub paddles: Vec<SidePoint>,
+    /// Where the ball is served from.
+    pub ball: Vec2,
+    /// Where each player's score is shown.
+    pub scores: Vec<SidePoint>,
+    /// Where the serve countdown is shown.
+    pub countdown: Vec2,
+    /// Where power-ups turn up.
+    #[serde(default = "default_power_up_area")]
+    pub power_up_area: Area,
+    /// Obstacles that are there from the start of each game.
+    #[serde(default)]
+    pub obstacles: Vec<Blueprint>,
+    /// Obstacles that turn up every so often during a game.
+    #[serde(default)]
+    pub timed_obstacles: Vec<TimedObstacle>,
+}
+
+/// Power-ups turn up in the middle of the court unless an arena says
+/// otherwise.
+const fn default_power_up_area() -> Area {
+    Area {
+        position: Vec2::ZERO,
+        size: Vec2::new(100.0, 300.0),
+    }
+}
+
+/// An obstacle that turns up every so often during a game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedObstacle {
+    /// How many seconds apart the obstacles turn up.
+    pub every: f32,
+    /// The obstacle that turns up. It usually has a lifetime, or the arena
+    /// will fill up with them.
+    pub obstacle: Blueprint,
+}
+
+/// Why an arena can't be played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Invalid {
+    /// A player is missing one of the parts every player needs.
+    Missing {
+        /// The part that is missing, as it appears in the file.
+        part: &'static str,
+        /// The player it is missing for.
+        side: Side,
+    },
+    /// There aren't enough paddles for a game.
+    TooFewPlayers,
+    /// A rectangle or obstacle has no area.
+    Empty {
+        /// The kind of rectangle, as it appears in the file.
+        part: &'static str,
+    },
+    /// A number that has to be positive isn't.
+    NotPositive {
+        /// The number, as it appears in the file.
+        part: &'static str,
+    },
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { part, side } => write!(f, "{part} has nothing for {side:?}"),
+            Self::TooFewPlayers => write!(f, "paddles must be on at least two sides"),
+            Self::Empty { part } => write!(f, "{part} must have a positive width and height"),
+            Self::NotPositive { part } => write!(f, "{part} must be more than 0"),
+        }
+    }
+}
+
+impl std::error::Error for Invalid {}
+
+impl Arena {
+    /// The sides that have a player, in the order players are numbered.
+    pub fn sides(&self) -> Vec<Side> {
+        Side::ALL
+            .into_iter()
+            .filter(|&side| self.paddles.iter().any(|p| p.side == side))
+            .collect()
+    }
+
+    /// Checks that the arena can be played: there need to be paddles on at
+    /// least two sides, every player needs a goal and a score, every paddle
+    /// needs a zone, and every rectangle needs an area.
+    pub fn validate(&self) -> Result<(), Invalid> {
+        let has_area = |size: Vec2| size.x > 0.0 && size.y > 0.0;
+        for (part, sizes) in [
+            (
+                "walls",
+                self.walls.iter().map(|a| a.size).collect::<Vec<_>>(),
+            ),
+            ("goals", self.goals.iter().map(|a| a.size).collect()),
+            (
+                "paddle_zones",
+                self.paddle_zones.iter().map(|a| a.size).collect(),
+            ),
+            ("power_up_area", vec![self.power_up_area.size]),
+        ] {
+            if !sizes.into_iter().all(has_area) {
+                return Err(Invalid::Empty { part });
+            }
+        }
+
+        let blueprints = self
+            .obstacles
+            .iter()
+            .chain(self.timed_obstacles.iter().map(|timed| &timed.obstacle));
+        for blueprint in blueprints {
+            if !blueprint.shape.has_area() {
+                return Err(Invalid::Empty { part: "obstacles" });
+            }
+            if blueprint.lifetime.is_some_and(|lifetime| lifetime <= 0.0) {
+                return Err(Invalid::NotPositive { part: "lifetime" });
+            }
+        }
+        if self.timed_obstacles.iter().any(|timed| timed.every <= 0.0) {
+            return Err(Invalid::NotPositive { part: "every" });
+        }
+
+        let sides = self.sides();
+        if sides.len() < 2 {
+            return Err(Invalid::TooFewPlayers);
+        }
+        for side in sides {
+            let missing = |part, found: bool| {
+                if found {
+                    Ok(())
+                } else {
+                    Err(Invalid::Missing { part, side })
+                }
+            };
+            missing("goals", self.goals.iter().any(|a| a.side == side))?;
+            let zoned = self.paddles.iter().filter(|p| p.side == side).all(|p| {
+                self.paddle_zones
+                    .iter()
+                    .any(|a| a.side == side && a.role == p.role)
+            });
+            missing("paddle_zones", zoned)?;
+            missing("scores", self.scores.iter().any(|p| p.side == side))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for Arena {
+    /// The classic court, used when no arena file has been loaded.
+    fn default() -> Self {
+        let half_width = TOP_WALL_SIZE.x / 2.0;
+        let height = TOP_WALL_POSITION.y - BOTTOM_WALL_POSITION.y;
+        Self {
+            name: "Classic".to_string(),
+            walls: vec![
+                Area {
+                    position: TOP_WALL_POSITION,
+                    size: TOP_WALL_SIZE,
+                },
+                Area {
+                    position: BOTTOM_WALL_POSITION,
+                    size: BOTTOM_WALL_SIZE,
+                },
+            ],
+            goals: vec![
+                SideArea {
+                    side: Side::Left,
+                    role: Role::Defender,
+                    position: Vec2::new(-half_width, 0.0),
+                    size: Vec2::new(25.0, height),
+                },
+                SideArea {
+                    side: Side::Right,
+                    role: Role::Defender,
+                    position: Vec2::new(half_width, 0.0),
+                    size: Vec2::new(25.0, height),
+                },
+            ],
+            paddle_zones: vec![
+                SideArea {
+                    side: Side::Left,
+                    role: Role::Defender,
+                    position: Vec2::new(-half_width / 2.0, 0.0),
+                    size: Vec2::new(half_width, height),
+                },
+                SideArea {
+                    side: Side::Right,
+                    role: Role::Defender,
+                    position: Vec2::new(half_width / 2.0, 0.0),
+                    size: Vec2::new(half_width, height),
+                },
+            ],
+            paddles: vec![
+                SidePoint {
+                    side: Side::Left,
+                    role: Role::Defender,
+                    position: LEFT_PADDLE_STARTING_POSITION,
+                },
+                SidePoint {
+                    side: Side::Right,
+                    role: Role::Defender,
+                    position: RIGHT_PADDLE_STARTING_POSITION,
+                },
+            ],
+            ball: BALL_DEFAULT_STARTING_POSITION,
+            scores: vec![
+                SidePoint {
+                    side: Side::Left,
+                    role: Role::Defender,
+                    position: LEFT_SCORE_POSITION,
+                },
+                SidePoint {
+                    side: Side::Right,
+                    role: Role::Defender,
+                    position: RIGHT_SCORE_POSITION,
+                },
+            ],
+            countdown: COUNTDOWN_POSITION,
+            power_up_area: default_power_up_area(),
+            obstacles: Vec::new(),
+            timed_obstacles: Vec::new(),
+        }
+    }
+}
+
+/// Loads arenas from `.arena.ron` files, refusing any that can't be played.
+#[derive(Debug, Default)]
+pub struct Loader;
+
+impl AssetLoader for Loader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let arena: Arena = ron::de::from_bytes(bytes)?;
+            arena.validate()?;
+            load_context.set_default_asset(LoadedAsset::new(arena));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[EXTENSION]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_shipped_arenas_are_playable() {
+        for file in crate::resources::Arenas::FILES {
+            let path = format!("{}/assets/{file}", env!("CARGO_MANIFEST_DIR"));
+            let bytes = std::fs::read(&path).unwrap();
+            let arena: Arena = ron::de::from_bytes(&bytes).unwrap();
+            assert_eq!(arena.validate(), Ok(()), "{path}");
+        }
+    }
+
+    #[test]
+    fn test_classic_arena_matches_the_default() {
+        let path = format!(
+            "{}/assets/arenas/classic.arena.ron",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let arena: Arena = ron::de::from_bytes(&std::fs::read(path).unwrap()).unwrap();
+        assert_eq!(arena, Arena::default());
+    }
+
+    #[test]
+    fn test_arenas_load_through_the_asset_server() {
+        use bevy::{
+            asset::AssetPlugin,
+            prelude::{AddAsset, App, Assets, MinimalPlugins},
+        };
+
+        use crate::resources::Arenas;
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin::default())
+            .add_asset::<Arena>()
+            .init_asset_loader::<Loader>()
+            .init_resource::<Arenas>();
+
+        let loaded = |app: &App| {
+            let arenas = app.world.resource::<Arenas>();
+            let assets = app.world.resource::<Assets<Arena>>();
+            arenas.handles.iter().all(|handle| assets.contains(handle))
+        };
+        for _ in 0..200 {
+            app.update();
+            if loaded(&app) {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        assert!(loaded(&app));
+        let arenas = app.world.resource::<Arenas>();
+        let assets = app.world.resource::<Assets<Arena>>();
+        assert_eq!(arenas.selected(assets).unwrap().name, "Classic");
+    }
+
+    #[test]
+    fn test_timed_obstacles_need_a_time() {
+        use crate::component::obstacle::Shape;
+
+        let mut arena = Arena::default();
+        arena.timed_obstacles.push(TimedObstacle {
+            every: 0.0,
+            obstacle: Blueprint {
+                shape: Shape::Circle(10.0),
+                position: Vec2::ZERO,
+                rotation: 0.0,
+                spin: 0.0,
+                path: Vec::new(),
+                speed: 0.0,
+                lifetime: Some(2.0),
+            },
+        });
+        assert_eq!(
+            arena.validate(),
+            Err(Invalid::NotPositive { part: "every" })
+        );
+
+        arena.timed_obstacles[0].every = 5.0;
+        assert_eq!(arena.validate(), Ok(()));
+
+        arena.timed_obstacles[0].obstacle.shape = Shape::Polygon(vec![Vec2::ZERO, Vec2::X]);
+        assert_eq!(arena.validate(), Err(Invalid::Empty { part: "obstacles" }));
+    }
+
+    #[test]
+    fn test_arena_needs_both_players() {
+        let mut arena = Arena::default();
+        arena.goals.retain(|goal| goal.side == Side::Left);
+        assert_eq!(
+            arena.validate(),
+            Err(Invalid::Missing {
+                part: "goals",
+                side: Side::Right
+            })
+        );
+
+        arena.paddles.retain(|paddle| paddle.side == Side::Left);
+        assert_eq!(arena.validate(), Err(Invalid::TooFewPlayers));
+    }
+
+    #[test]
+    fn test_attackers_need_their_own_zone() {
+        let mut arena = Arena::default();
+        arena.paddles.push(SidePoint {
+            side: Side::Right,
+            role: Role::Attacker,
+            position: Vec2::new(50.0, 0.0),
+        });
+        assert_eq!(
+            arena.validate(),
+            Err(Invalid::Missing {
+                part: "paddle_zones",
+                side: Side::Right
+            })
+        );
+
+        arena.paddle_zones.push(SideArea {
+            side: Side::Right,
+            role: Role::Attacker,
+            position: Vec2::new(50.0, 0.0),
+            size: Vec2::new(50.0, 500.0),
+        });
+        assert_eq!(arena.validate(), Ok(()));
+    }
+}
@@ -0,0 +1,366 @@
+use std::time::Duration;
+
+use bevy::{
+    prelude::{Component, Transform, Vec2, Vec3},
+    time::{Timer, TimerMode},
+};
+
+use super::collider;
+use crate::constants::{MAX_PREDICTED_BOUNCES, PADDLE_SPEED_MULTIPLIER};
+
+/// How an AI works out where the ball is going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Follows the ball's current heading in a straight line, ignoring the
+    /// walls.
+    Extrapolate,
+    /// Plays the ball's path forward, bouncing it off the walls the same way
+    /// the game does.
+    Simulate,
+}
+
+/// How well a computer-controlled paddle plays.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difficulty {
+    /// How long the AI takes to react to the ball, in seconds. It only looks
+    /// at the ball this often.
+    pub reaction_delay: f32,
+    /// How well the AI reads where the ball is going, from 0.0 (it just
+    /// follows the ball) to 1.0 (it goes straight to where the ball is headed).
+    pub accuracy: f32,
+    /// The fastest the AI moves its paddle.
+    pub max_speed: f32,
+    /// How quickly the AI can speed its paddle up or slow it down, in units
+    /// per second per second.
+    pub acceleration: f32,
+    /// The most the AI misjudges where the ball is going, in either direction.
+    pub error: f32,
+    /// How the AI works out where the ball is going.
+    pub strategy: Strategy,
+}
+
+impl Difficulty {
+    /// Slow to react and easily fooled.
+    pub const EASY: Self = Self {
+        reaction_delay: 0.4,
+        accuracy: 0.3,
+        max_speed: PADDLE_SPEED_MULTIPLIER * 0.6,
+        acceleration: 200.0,
+        error: 30.0,
+        strategy: Strategy::Extrapolate,
+    };
+
+    /// A fair match for most players.
+    pub const NORMAL: Self = Self {
+        reaction_delay: 0.2,
+        accuracy: 0.7,
+        max_speed: PADDLE_SPEED_MULTIPLIER * 0.85,
+        acceleration: 400.0,
+        error: 15.0,
+        strategy: Strategy::Extrapolate,
+    };
+
+    /// Quick, accurate, sees bank shots coming, and moves as fast as a player
+    /// can.
+    pub const HARD: Self = Self {
+        reaction_delay: 0.08,
+        accuracy: 1.0,
+        max_speed: PADDLE_SPEED_MULTIPLIER,
+        acceleration: 800.0,
+        error: 4.0,
+        strategy: Strategy::Simulate,
+    };
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+/// Controls the movement of a paddle with the computer, in place of the
+/// keyboard.
+#[derive(Debug, Clone, Component)]
+pub struct Ai {
+    /// How well the AI plays.
+    pub difficulty: Difficulty,
+    /// Counts down to the next time the AI looks at the ball.
+    reaction: Timer,
+    /// The height the AI is trying to move its paddle to.
+    target: f32,
+}
+
+impl Ai {
+    /// Creates a new AI that plays at the given difficulty.
+    pub fn new(difficulty: Difficulty) -> Self {
+        Self {
+            difficulty,
+            reaction: Timer::from_seconds(difficulty.reaction_delay, TimerMode::Repeating),
+            target: 0.0,
+        }
+    }
+
+    /// The height the AI is trying to move its paddle to.
+    pub const fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// Advances the reaction timer. Returns true when it is time for the AI to
+    /// look at the ball again.
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        self.reaction.tick(delta).just_finished()
+    }
+
+    /// Decides where to move the paddle given where the ball is and how it's
+    /// moving. `walls` are only used by AIs that simulate the ball's path.
+    /// `noise` is a number from -1.0 to 1.0 that is scaled by the
+    /// difficulty's error.
+    pub fn retarget(
+        &mut self,
+        paddle: &Transform,
+        ball: &Transform,
+        ball_vel: Vec2,
+        walls: &[Transform],
+        noise: f32,
+    ) {
+        let ball_pos = ball.translation.truncate();
+        // The ball meets the paddle when their edges touch, not their centres
+        let reach = (paddle.scale.x + ball.scale.x) / 2.0;
+        let line_x = ball_vel.x.signum().mul_add(-reach, paddle.translation.x);
+
+        let headed_for = match self.difficulty.strategy {
+            Strategy::Extrapolate => extrapolate_intercept(ball_pos, ball_vel, line_x),
+            Strategy::Simulate => predict_intercept(ball, ball_vel, line_x, walls),
+        };
+        let Some(headed_for) = headed_for else {
+            // Wait in the middle for the ball to come back
+            self.target = 0.0;
+            return;
+        };
+
+        let guess = (headed_for - ball_pos.y).mul_add(self.difficulty.accuracy, ball_pos.y);
+        self.target = noise.mul_add(self.difficulty.error, guess);
+    }
+
+    /// The vertical velocity to give the paddle at `paddle_y` for the next
+    /// step of `step` seconds. The paddle is currently moving at
+    /// `velocity_y`, and `friction` slows it down after every step.
+    ///
+    /// The paddle can't change speed faster than the difficulty's
+    /// acceleration, so it starts slowing down early enough, with help from
+    /// friction, to stop on the target instead of overshooting it.
+    pub fn steer(&self, paddle_y: f32, velocity_y: f32, friction: f32, step: f32) -> f32 {
+        let Difficulty {
+            max_speed,
+            acceleration,
+            ..
+        } = self.difficulty;
+        let distance = self.target - paddle_y;
+
+        // The fastest the paddle can be going and still stop in time
+        let braking = acceleration + friction / step;
+        let stopping_speed = (2.0 * braking * distance.abs()).sqrt();
+        let desired = distance.signum() * max_speed.min(stopping_speed).min(distance.abs() / step);
+
+        let max_change = acceleration * step;
+        velocity_y + (desired - velocity_y).clamp(-max_change, max_change)
+    }
+}
+
+/// Mirrors a transform across the diagonal, swapping its x and y.
+///
+/// The AI works out where the ball is going as if the paddle moved up and
+/// down, so a paddle that moves left and right plays in a mirrored court.
+pub fn transpose(tf: &Transform) -> Transform {
+    let swap = |v: Vec3| Vec3::new(v.y, v.x, v.z);
+    Transform {
+        translation: swap(tf.translation),
+        scale: swap(tf.scale),
+        ..*tf
+    }
+}
+
+/// Picks out the ball the paddle should go for when there is more than one:
+/// the one that will reach it soonest, or the closest one if none of them are
+/// heading its way.
+pub fn most_urgent<'a>(
+    paddle: &Transform,
+    balls: impl IntoIterator<Item = (&'a Transform, Vec2)>,
+) -> Option<(&'a Transform, Vec2)> {
+    let paddle_x = paddle.translation.x;
+    // Balls on their way come first, soonest first, then the rest, closest
+    // first
+    let urgency = |(ball, vel): &(&Transform, Vec2)| {
+        let distance = paddle_x - ball.translation.x;
+        if distance * vel.x > 0.0 {
+            (false, distance / vel.x)
+        } else {
+            (true, distance.abs())
+        }
+    };
+    balls.into_iter().min_by(|a, b| {
+        let ((a_away, a), (b_away, b)) = (urgency(a), urgency(b));
+        a_away.cmp(&b_away).then(a.total_cmp(&b))
+    })
+}
+
+/// Where the ball, at `ball_pos` moving at `ball_vel`, will cross the vertical
+/// line at `line_x` if it carries straight on. Returns `None` if the ball is
+/// heading away from the line.
+pub fn extrapolate_intercept(ball_pos: Vec2, ball_vel: Vec2, line_x: f32) -> Option<f32> {
+    if (line_x - ball_pos.x) * ball_vel.x <= 0.0 {
+        return None;
+    }
+    let time_to_reach = (line_x - ball_pos.x) / ball_vel.x;
+    Some(ball_vel.y.mul_add(time_to_reach, ball_pos.y))
+}
+
+/// Where the ball's centre will cross the vertical line at `line_x`, following
+/// its path as it bounces off the `walls`.
+///
+/// The path is swept against the walls with the same collision code the game
+/// uses to move the ball, so the two agree. Spin is not taken into account.
+///
+/// Returns `None` if the ball is heading away from the line, or if it bounces
+/// too many times to be worth following.
+pub fn predict_intercept(
+    ball: &Transform,
+    ball_vel: Vec2,
+    line_x: f32,
+    walls: &[Transform],
+) -> Option<f32> {
+    let mut ball = *ball;
+    let mut velocity = ball_vel;
+
+    for _ in 0..=MAX_PREDICTED_BOUNCES {
+        let position = ball.translation.truncate();
+        let intercept = extrapolate_intercept(position, velocity, line_x)?;
+        let displacement = Vec2::new(line_x, intercept) - position;
+
+        let hit = walls
+            .iter()
+            .filter_map(|wall| collider::sweep(&ball, displacement, wall))
+            .min_by(|a, b| a.time.total_cmp(&b.time));
+        let Some(hit) = hit else {
+            return Some(intercept);
+        };
+
+        ball.translation += (displacement * hit.time).extend(0.0);
+        velocity = collider::reflect(velocity, hit.normal);
+    }
+
+    None
+}
+
+impl Default for Ai {
+    fn default() -> Self {
+        Self::new(Difficulty::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_retarget() {
+        let paddle = Transform::from_xyz(110.0, 0.0, 0.0).with_scale((10.0, 100.0, 1.0).into());
+        let ball = Transform::from_xyz(0.0, 0.0, 0.0).with_scale((10.0, 10.0, 1.0).into());
+        let mut ai = Ai::new(Difficulty {
+            accuracy: 1.0,
+            error: 10.0,
+            strategy: Strategy::Extrapolate,
+            ..Difficulty::HARD
+        });
+
+        // Coming toward the paddle's face at x = 100, rising 1 unit for every
+        // 2 across
+        ai.retarget(&paddle, &ball, Vec2::new(50.0, 25.0), &[], 0.0);
+        assert_eq!(ai.target(), 50.0);
+
+        // Misjudged by the full error
+        ai.retarget(&paddle, &ball, Vec2::new(50.0, 25.0), &[], -1.0);
+        assert_eq!(ai.target(), 40.0);
+
+        // Going the other way
+        ai.retarget(&paddle, &ball, Vec2::new(-50.0, 25.0), &[], 0.0);
+        assert_eq!(ai.target(), 0.0);
+
+        // A poor reader of the ball only goes part of the way
+        let paddle = Transform::from_xyz(-110.0, 0.0, 0.0).with_scale((10.0, 100.0, 1.0).into());
+        let ball = ball.with_translation((0.0, 10.0, 0.0).into());
+        let mut ai = Ai::new(Difficulty {
+            accuracy: 0.5,
+            error: 0.0,
+            ..Difficulty::EASY
+        });
+        ai.retarget(&paddle, &ball, Vec2::new(-50.0, 25.0), &[], 0.0);
+        assert_eq!(ai.target(), 35.0);
+    }
+
+    #[test]
+    fn test_predict_intercept_off_a_wall() {
+        let ball = Transform::from_xyz(0.0, 0.0, 0.0).with_scale((10.0, 10.0, 1.0).into());
+        let ceiling = Transform::from_xyz(0.0, 55.0, 0.0).with_scale((500.0, 10.0, 1.0).into());
+
+        // Straight there would be y = 100, but the ceiling stops the ball's
+        // centre at y = 45 and sends it back down
+        assert_eq!(
+            extrapolate_intercept(Vec2::ZERO, Vec2::new(50.0, 50.0), 100.0),
+            Some(100.0)
+        );
+        let intercept = predict_intercept(&ball, Vec2::new(50.0, 50.0), 100.0, &[ceiling]).unwrap();
+        assert!((intercept - -10.0).abs() < 1e-3);
+
+        // Nothing to predict when the ball is going the other way
+        assert_eq!(
+            predict_intercept(&ball, Vec2::new(-50.0, 50.0), 100.0, &[ceiling]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_steer() {
+        let mut ai = Ai::new(Difficulty {
+            accuracy: 1.0,
+            error: 0.0,
+            max_speed: 40.0,
+            acceleration: 100.0,
+            strategy: Strategy::Extrapolate,
+            ..Difficulty::HARD
+        });
+        let paddle = Transform::from_xyz(105.0, 0.0, 0.0).with_scale((10.0, 100.0, 1.0).into());
+        let ball = Transform::from_xyz(0.0, 0.0, 0.0).with_scale((0.0, 0.0, 1.0).into());
+        ai.retarget(&paddle, &ball, Vec2::new(50.0, 25.0), &[], 0.0);
+        assert_eq!(ai.target(), 50.0);
+
+        // From a standstill it can only speed up so fast
+        assert_eq!(ai.steer(0.0, 0.0, 0.0, 0.1), 10.0);
+        // Far away and already moving, so it goes as fast as it can
+        assert_eq!(ai.steer(0.0, 40.0, 0.0, 0.1), 40.0);
+        // Close, so it slows down
+        assert!(ai.steer(45.0, 40.0, 0.0, 0.1) < 40.0);
+        // Overshot, so it turns back, no faster than it can slow down
+        assert_eq!(ai.steer(60.0, 0.0, 0.0, 0.1), -10.0);
+    }
+
+    #[test]
+    fn test_most_urgent() {
+        let paddle = Transform::from_xyz(100.0, 0.0, 0.0);
+        let near = Transform::from_xyz(80.0, 0.0, 0.0);
+        let far = Transform::from_xyz(0.0, 0.0, 0.0);
+
+        // The far ball is moving fast enough to get there first
+        let balls = [(&near, Vec2::new(10.0, 0.0)), (&far, Vec2::new(500.0, 0.0))];
+        assert_eq!(most_urgent(&paddle, balls).unwrap().0, &far);
+
+        // Neither is on its way, so the closest one is watched
+        let balls = [
+            (&far, Vec2::new(-10.0, 0.0)),
+            (&near, Vec2::new(-10.0, 0.0)),
+        ];
+        assert_eq!(most_urgent(&paddle, balls).unwrap().0, &near);
+
+        assert!(most_urgent(&paddle, []).is_none());
+    }
+}
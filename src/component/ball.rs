@@ -1,10 +1,11 @@
-use std::ops::Mul;
-
-use super::velocity::Velocity;
-use crate::constants::{BALL_DEFAULT_STARTING_POSITION, BALL_SCALE, DEFAULT_BALL_SPEED};
-use bevy::prelude::{Bundle as BevyBundle, Color, Component, Transform, Vec2};
+use super::{paddle::Side, spin::Spin, velocity::Velocity};
+use crate::constants::{BALL_DEFAULT_STARTING_POSITION, BALL_SCALE};
+use bevy::prelude::{
+    Bundle as BevyBundle, Color, Component, Deref, DerefMut, Entity, Transform, Vec2,
+};
+use bevy::time::Timer;
 use bevy_prototype_lyon::{
-    prelude::{Fill, GeometryBuilder, ShapeBundle},
+    prelude::{Fill, GeometryBuilder, ShapeBundle, Stroke},
     shapes,
 };
 
@@ -12,6 +13,64 @@ use bevy_prototype_lyon::{
 #[derive(Component, Default)]
 pub struct Ball;
 
+/// Where the ball is put back to after each point.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct StartingPosition(pub Vec2);
+
+impl Default for StartingPosition {
+    fn default() -> Self {
+        Self(BALL_DEFAULT_STARTING_POSITION)
+    }
+}
+
+/// How much faster than its velocity the ball actually moves. Lets effects
+/// change the ball's pace without getting in the way of the rally speeding
+/// it up.
+#[derive(Component, Clone, Copy, Debug, Deref, DerefMut, PartialEq)]
+pub struct SpeedFactor(pub f32);
+
+impl Default for SpeedFactor {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// The paddle that last hit the ball during the current rally, if any.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LastHit {
+    /// The side of the paddle that hit the ball.
+    pub side: Option<Side>,
+    /// The paddle that hit the ball.
+    pub paddle: Option<Entity>,
+}
+
+/// A ball that has gone into a goal while other balls are still in play. It
+/// sits out the rest of the point.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Out;
+
+/// A ball put into play by a power-up. It is taken off the court when the
+/// point ends, instead of lining up for the next serve.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Extra;
+
+/// Holds the ball against a sticky paddle until its player lets go of it.
+#[derive(Component, Clone, Debug)]
+pub struct Stuck {
+    /// The paddle the ball is stuck to.
+    pub paddle: Entity,
+    /// Where the ball is, relative to the paddle.
+    pub offset: Vec2,
+    /// The velocity the ball is let go with.
+    pub velocity: Vec2,
+    /// Counts down to the paddle letting go on its own.
+    pub timer: Timer,
+    /// Whether the paddle's player has pressed serve to let go of the ball.
+    /// Presses are read every frame, and this holds on to one until the next
+    /// fixed step acts on it.
+    pub served: bool,
+}
+
 /// A bundle of components that can be used to spawn a ball.
 #[derive(BevyBundle)]
 pub struct Bundle {
@@ -20,8 +79,18 @@ pub struct Bundle {
     circle: ShapeBundle,
     /// Controls the color of the ball.
     fill: Fill,
+    /// Draws a line on the ball so that its spin can be seen.
+    stroke: Stroke,
     /// How fast the ball is moving.
     pub velocity: Velocity,
+    /// How fast the ball is spinning.
+    pub spin: Spin,
+    /// Where the ball goes back to after each point.
+    pub starting_position: StartingPosition,
+    /// How much faster than its velocity the ball moves.
+    pub speed_factor: SpeedFactor,
+    /// Who hit the ball last.
+    pub last_hit: LastHit,
     ball: Ball,
 }
 
@@ -34,27 +103,33 @@ impl Bundle {
     }
 
     #[must_use]
-    /// Sets the position of the ball.
+    /// Sets the spin of the ball, in radians per second.
+    pub fn with_spin(mut self, spin: f32) -> Self {
+        self.spin = spin.into();
+        self
+    }
+
+    #[must_use]
+    /// Sets the position of the ball, and where it goes back to after each
+    /// point.
     pub fn with_position(mut self, pos: Vec2) -> Self {
         self.circle.transform.translation = (pos, 0.0).into();
+        self.starting_position = StartingPosition(pos);
         self
     }
 }
 
-/// Generates a random 2D vector with a maximum magnitude of 1.
-fn random_vec2() -> Vec2 {
-    Vec2::new(rand::random::<f32>(), rand::random::<f32>())
-}
-
 impl Default for Bundle {
     /// Creates a new ball bundle with default values. The ball will look like
     /// a white circle, will begin at the default position, have the default
-    /// collision box, and will have a random velocity scaled by the default
-    /// speed.
+    /// collision box, and will sit still until it is served.
     fn default() -> Self {
         Self {
             circle: ShapeBundle {
-                path: GeometryBuilder::build_as(&shapes::Circle::default()),
+                path: GeometryBuilder::new()
+                    .add(&shapes::Circle::default())
+                    .add(&shapes::Line(Vec2::ZERO, Vec2::new(0.0, 0.8)))
+                    .build(),
                 transform: Transform {
                     translation: (BALL_DEFAULT_STARTING_POSITION, 0.0).into(),
                     scale: BALL_SCALE,
@@ -63,11 +138,13 @@ impl Default for Bundle {
                 ..Default::default()
             },
             fill: Fill::color(Color::WHITE),
-            velocity: random_vec2()
-                .mul(DEFAULT_BALL_SPEED)
-                .clamp((25.0, 25.0).into(), (100.0, 100.0).into())
-                .into(),
-            ball: Ball::default(),
+            stroke: Stroke::new(Color::BLACK, 0.1),
+            velocity: Velocity::default(),
+            spin: Spin::default(),
+            starting_position: StartingPosition::default(),
+            speed_factor: SpeedFactor::default(),
+            last_hit: LastHit::default(),
+            ball: Ball,
         }
     }
 }
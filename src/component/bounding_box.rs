@@ -6,7 +6,7 @@ use bevy::{
     sprite::{collide_aabb::collide, Sprite, SpriteBundle},
 };
 
-use super::paddle::Side;
+use super::paddle::{Role, Side};
 
 /// A component that is used to mark an entity as being able to detect scores.
 #[derive(BevyComponent, Clone, Default)]
@@ -26,6 +26,8 @@ pub struct BoundingBox {
     // TODO: probably better to just associate each player with a score zone instead,
     // to make it queryable.
     pub side: Side,
+    /// For paddle zones, which of the side's paddles it belongs to.
+    pub role: Role,
 }
 
 impl Bundle {
@@ -56,6 +58,13 @@ impl Bundle {
         self.bounding_box.side = side;
         self
     }
+
+    #[must_use]
+    /// Sets which of the side's paddles the bounding box belongs to.
+    pub const fn for_role(mut self, role: Role) -> Self {
+        self.bounding_box.role = role;
+        self
+    }
 }
 
 impl Default for Bundle {
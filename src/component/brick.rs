@@ -0,0 +1,93 @@
+use bevy::{
+    prelude::{Bundle as BevyBundle, Color, Component, Transform, Vec2},
+    sprite::{Sprite, SpriteBundle},
+};
+
+use super::collider::Collider;
+use crate::constants::BRICK_SIZE;
+
+/// A brick in a game of Breakout, which breaks after it has been hit enough
+/// times.
+#[derive(Debug, Clone, Copy, Component, Default, PartialEq, Eq)]
+pub struct Brick {
+    /// How many more hits it takes to break the brick.
+    pub hits: u32,
+}
+
+impl Brick {
+    /// Records a hit from the ball. Returns true if that broke the brick.
+    pub const fn hit(&mut self) -> bool {
+        self.hits = self.hits.saturating_sub(1);
+        self.hits == 0
+    }
+
+    /// The color of the brick, which shows how many more hits it takes.
+    pub const fn color(&self) -> Color {
+        match self.hits {
+            0 | 1 => Color::CYAN,
+            2 => Color::GREEN,
+            3 => Color::YELLOW,
+            4 => Color::ORANGE,
+            _ => Color::RED,
+        }
+    }
+}
+
+/// A bundle that creates a brick the ball bounces off.
+#[derive(BevyBundle)]
+pub struct Bundle {
+    #[bundle]
+    /// Controls the look, position and size of the brick.
+    sprite: SpriteBundle,
+    collider: Collider,
+    /// How many more hits it takes to break the brick.
+    pub brick: Brick,
+}
+
+impl Bundle {
+    /// Creates a brick that takes `hits` hits to break.
+    pub fn new(hits: u32) -> Self {
+        let brick = Brick { hits };
+        Self {
+            sprite: SpriteBundle {
+                sprite: Sprite {
+                    color: brick.color(),
+                    ..Default::default()
+                },
+                transform: Transform::from_scale(BRICK_SIZE.extend(1.0)),
+                ..Default::default()
+            },
+            collider: Collider,
+            brick,
+        }
+    }
+
+    #[must_use]
+    /// Sets the position of the brick.
+    pub const fn at(mut self, pos: Vec2) -> Self {
+        self.sprite.transform.translation = pos.extend(0.0);
+        self
+    }
+}
+
+impl Default for Bundle {
+    /// By default, bricks break in one hit.
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bricks_break_after_their_hits() {
+        let mut brick = Brick { hits: 2 };
+        assert_eq!(brick.color(), Color::GREEN);
+        assert!(!brick.hit());
+        assert_eq!(brick.color(), Color::CYAN);
+        assert!(brick.hit());
+        assert!(brick.hit());
+    }
+}
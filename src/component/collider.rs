@@ -1,40 +1,49 @@
-use bevy::{
-    prelude::{Component as BevyComponent, Vec2},
-    sprite::collide_aabb::Collision,
-};
+use bevy::prelude::{Component as BevyComponent, Entity, Transform, Vec2};
 
 /// A component that indicates that an entity should be treated as collidable.
 #[derive(BevyComponent, Clone, Default, Debug)]
 pub struct Collider;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 /// Event for when a collision happens.
 pub struct Event {
     /// The intensity of the collision. This is the magnitude of the relative
     /// velocity of the two colliding objects.
     pub intensity: f32,
-    /// The side of the collision.
-    pub kind: Collision,
-}
-
-impl Clone for Event {
-    fn clone(&self) -> Self {
-        Self {
-            intensity: self.intensity,
-            kind: unsafe { std::mem::transmute_copy(&self.kind) },
-        }
-    }
+    /// The normal of the surface that was hit, after the collision was
+    /// resolved.
+    pub normal: Vec2,
+    /// The entity that was hit, if it is known.
+    pub struck: Option<Entity>,
 }
 
 impl Event {
     /// Creates a new collision event.
-    pub fn new(kind: Collision, vel_a: impl Into<Vec2>, vel_b: impl Into<Vec2>) -> Self {
+    pub fn new(normal: Vec2, vel_a: impl Into<Vec2>, vel_b: impl Into<Vec2>) -> Self {
         // Relative velocity determines the intensity of the collision. If the
         // two objects are moving in the same direction, the collision is
         // less intense. If the two objects are moving in opposite directions,
         // the collision is more intense.
         let intensity = (vel_a.into() - vel_b.into()).length();
-        Self { intensity, kind }
+        Self {
+            intensity,
+            normal,
+            struck: None,
+        }
+    }
+
+    #[must_use]
+    /// Sets the normal of the surface that was hit.
+    pub const fn with_normal(mut self, normal: Vec2) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    #[must_use]
+    /// Sets the entity that was hit.
+    pub const fn with_struck(mut self, struck: Entity) -> Self {
+        self.struck = Some(struck);
+        self
     }
 }
 
@@ -42,11 +51,325 @@ impl Default for Event {
     fn default() -> Self {
         Self {
             intensity: 1.0,
-            kind: Collision::Inside,
+            normal: Vec2::ZERO,
+            struck: None,
+        }
+    }
+}
+
+/// Where a moving box first touched another box while being swept along a
+/// path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+    /// How far along the path the boxes touched, from 0.0 (the start) to 1.0
+    /// (the end).
+    pub time: f32,
+    /// The normal of the surface that was hit.
+    pub normal: Vec2,
+}
+
+/// Reflects a velocity off a surface with the given normal.
+pub fn reflect(velocity: Vec2, normal: Vec2) -> Vec2 {
+    velocity - 2.0 * velocity.dot(normal) * normal
+}
+
+/// How to separate two boxes that overlap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    /// The normal of the surface the mover should be pushed out through.
+    pub normal: Vec2,
+    /// How far the mover has to be pushed along the normal to stop
+    /// overlapping.
+    pub depth: f32,
+}
+
+impl Contact {
+    /// The smallest translation that separates the two boxes.
+    pub fn translation(&self) -> Vec2 {
+        self.normal * self.depth
+    }
+}
+
+/// If the `mover` box overlaps the `collider` box, returns the shortest way to
+/// push the mover back out. The size of each box is taken from the scale of
+/// its Transform.
+pub fn contact(mover: &Transform, collider: &Transform) -> Option<Contact> {
+    let offset = mover.translation.truncate() - collider.translation.truncate();
+    let half_extents = (mover.scale.truncate() + collider.scale.truncate()) / 2.0;
+    let overlap = half_extents - offset.abs();
+
+    if overlap.x <= 0.0 || overlap.y <= 0.0 {
+        return None;
+    }
+
+    // Push out along whichever axis needs the smaller push
+    Some(if overlap.x < overlap.y {
+        Contact {
+            normal: Vec2::new(offset.x.signum(), 0.0),
+            depth: overlap.x,
+        }
+    } else {
+        Contact {
+            normal: Vec2::new(0.0, offset.y.signum()),
+            depth: overlap.y,
+        }
+    })
+}
+
+/// Sweeps the `mover` box along `displacement` and returns where it first
+/// touches the `collider` box. The size of each box is taken from the scale of
+/// its Transform.
+///
+/// Returns `None` if the boxes don't touch along the way, if the mover is
+/// moving away from the collider, or if the two already overlap at the start.
+pub fn sweep(mover: &Transform, displacement: Vec2, collider: &Transform) -> Option<Hit> {
+    let start = mover.translation.truncate();
+    // Growing the collider by the size of the mover lets us treat the mover
+    // as a single point travelling along a ray.
+    let half_extents = (mover.scale.truncate() + collider.scale.truncate()) / 2.0;
+    let min = collider.translation.truncate() - half_extents;
+    let max = collider.translation.truncate() + half_extents;
+
+    let mut entry = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut normal = Vec2::ZERO;
+
+    for axis in 0..2 {
+        let (from, delta) = (start[axis], displacement[axis]);
+
+        if delta == 0.0 {
+            // Not moving along this axis, so it has to already be lined up
+            if from <= min[axis] || from >= max[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let (near, far, facing) = if delta > 0.0 {
+            (min[axis], max[axis], -1.0)
+        } else {
+            (max[axis], min[axis], 1.0)
+        };
+        let near_time = (near - from) / delta;
+        let far_time = (far - from) / delta;
+
+        if near_time > entry {
+            entry = near_time;
+            normal = Vec2::ZERO;
+            normal[axis] = facing;
+        }
+        exit = exit.min(far_time);
+    }
+
+    if entry >= exit || exit <= 0.0 || !(0.0..=1.0).contains(&entry) {
+        return None;
+    }
+
+    Some(Hit {
+        time: entry,
+        normal,
+    })
+}
+
+/// The outline of a collider that isn't an axis-aligned box, in the
+/// collider's own space. Colliders without a hull are boxes the size of their
+/// Transform's scale.
+///
+/// Hulls are tested against the ball as a circle, and follow their
+/// Transform's position, rotation and scale.
+#[derive(BevyComponent, Clone, Debug, PartialEq)]
+pub enum Hull {
+    /// A circle around the collider's position.
+    Circle {
+        /// The radius of the circle.
+        radius: f32,
+    },
+    /// A closed polygon, given by its corners in order. It doesn't need to be
+    /// convex, but its edges shouldn't cross.
+    Polygon(Vec<Vec2>),
+}
+
+impl Hull {
+    /// The hull of a rectangle with the given width and height, centred on
+    /// the collider's position.
+    pub fn rectangle(size: Vec2) -> Self {
+        let half = size / 2.0;
+        Self::Polygon(vec![
+            Vec2::new(-half.x, -half.y),
+            Vec2::new(half.x, -half.y),
+            Vec2::new(half.x, half.y),
+            Vec2::new(-half.x, half.y),
+        ])
+    }
+
+    /// The hull's corners, moved into world space by its Transform.
+    fn corners(points: &[Vec2], transform: &Transform) -> Vec<Vec2> {
+        points
+            .iter()
+            .map(|point| transform.transform_point(point.extend(0.0)).truncate())
+            .collect()
+    }
+
+    /// If a circle overlaps the hull, returns the shortest way to push the
+    /// circle back out.
+    pub fn contact(&self, transform: &Transform, center: Vec2, radius: f32) -> Option<Contact> {
+        let (closest, inside) = match self {
+            Self::Circle {
+                radius: hull_radius,
+            } => {
+                let origin = transform.translation.truncate();
+                let hull_radius = hull_radius * transform.scale.x;
+                let offset = center - origin;
+                let surface = origin + offset.normalize_or_zero() * hull_radius;
+                (surface, offset.length() < hull_radius)
+            }
+            Self::Polygon(points) => {
+                let corners = Self::corners(points, transform);
+                let closest = edges(&corners)
+                    .map(|(a, b)| closest_on_segment(center, a, b))
+                    .min_by(|a, b| {
+                        a.distance_squared(center)
+                            .total_cmp(&b.distance_squared(center))
+                    })?;
+                (closest, contains(&corners, center))
+            }
+        };
+
+        let offset = center - closest;
+        let distance = offset.length();
+        if inside {
+            // Out through the nearest edge
+            Some(Contact {
+                normal: -offset.normalize_or_zero(),
+                depth: radius + distance,
+            })
+        } else if distance < radius {
+            Some(Contact {
+                normal: offset.normalize_or_zero(),
+                depth: radius - distance,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Sweeps a circle along `displacement` and returns where it first touches
+    /// the hull.
+    ///
+    /// Like `sweep`, returns `None` if they don't touch along the way or if
+    /// they already overlap at the start.
+    pub fn sweep(
+        &self,
+        transform: &Transform,
+        center: Vec2,
+        radius: f32,
+        displacement: Vec2,
+    ) -> Option<Hit> {
+        if self.contact(transform, center, radius).is_some() {
+            return None;
+        }
+
+        match self {
+            Self::Circle {
+                radius: hull_radius,
+            } => sweep_circle(
+                center,
+                displacement,
+                transform.translation.truncate(),
+                hull_radius * transform.scale.x + radius,
+            ),
+            Self::Polygon(points) => {
+                // Growing the polygon by the radius of the circle lets us
+                // treat the circle as a point: each edge is pushed out along
+                // its normals, and each corner becomes a circle.
+                let corners = Self::corners(points, transform);
+                let along_edges = edges(&corners).flat_map(|(a, b)| {
+                    let normal = (b - a).perp().normalize_or_zero();
+                    [normal, -normal].into_iter().filter_map(move |normal| {
+                        sweep_edge(
+                            center,
+                            displacement,
+                            a + normal * radius,
+                            b + normal * radius,
+                            normal,
+                        )
+                    })
+                });
+                let around_corners = corners
+                    .iter()
+                    .filter_map(|&corner| sweep_circle(center, displacement, corner, radius));
+                along_edges
+                    .chain(around_corners)
+                    .min_by(|a, b| a.time.total_cmp(&b.time))
+            }
         }
     }
 }
 
+/// Each edge of a closed polygon, as pairs of corners.
+fn edges(corners: &[Vec2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    corners
+        .iter()
+        .zip(corners.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+/// The point on the segment from `a` to `b` nearest to `point`.
+fn closest_on_segment(point: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+    let edge = b - a;
+    let along = (point - a).dot(edge) / edge.length_squared().max(f32::EPSILON);
+    a + edge * along.clamp(0.0, 1.0)
+}
+
+/// Whether a point is inside a closed polygon, by counting how many of its
+/// edges a ray from the point crosses.
+fn contains(corners: &[Vec2], point: Vec2) -> bool {
+    edges(corners)
+        .filter(|(a, b)| {
+            (a.y > point.y) != (b.y > point.y)
+                && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+        })
+        .count()
+        % 2
+        == 1
+}
+
+/// Where a point moving along `displacement` first crosses the segment from
+/// `a` to `b` while travelling against the segment's `normal`.
+fn sweep_edge(start: Vec2, displacement: Vec2, a: Vec2, b: Vec2, normal: Vec2) -> Option<Hit> {
+    let approach = displacement.dot(normal);
+    if approach >= 0.0 {
+        return None;
+    }
+    let time = (a - start).dot(normal) / approach;
+    if !(0.0..=1.0).contains(&time) {
+        return None;
+    }
+    let point = start + displacement * time;
+    let edge = b - a;
+    let along = (point - a).dot(edge);
+    (0.0..=edge.length_squared())
+        .contains(&along)
+        .then_some(Hit { time, normal })
+}
+
+/// Where a point moving along `displacement` first enters a circle.
+fn sweep_circle(start: Vec2, displacement: Vec2, center: Vec2, radius: f32) -> Option<Hit> {
+    let offset = start - center;
+    let a = displacement.length_squared();
+    let b = 2.0 * offset.dot(displacement);
+    let c = radius.mul_add(-radius, offset.length_squared());
+    let discriminant = b.mul_add(b, -4.0 * a * c);
+    if a == 0.0 || b >= 0.0 || discriminant < 0.0 {
+        return None;
+    }
+    let time = (-b - discriminant.sqrt()) / (2.0 * a);
+    (0.0..=1.0).contains(&time).then(|| Hit {
+        time,
+        normal: ((start + displacement * time - center) / radius).normalize_or_zero(),
+    })
+}
+
 #[cfg(test)]
 mod test {
 
@@ -57,13 +380,13 @@ mod test {
         // Two vectors moving at the same speed in opposite directions
         let vel_a = Vec2::new(5.0, 0.0);
         let vel_b = Vec2::new(-5.0, 0.0);
-        let event = Event::new(Collision::Inside, vel_a, vel_b);
+        let event = Event::new(Vec2::X, vel_a, vel_b);
         assert_eq!(event.intensity, 10.0);
 
         // Two vectors moving at the same speeds in the same direction
         let vel_a = Vec2::new(5.0, 0.0);
         let vel_b = Vec2::new(5.0, 0.0);
-        let event = Event::new(Collision::Inside, vel_a, vel_b);
+        let event = Event::new(Vec2::X, vel_a, vel_b);
         assert_eq!(event.intensity, 0.0);
 
         // Angled vectors
@@ -71,7 +394,145 @@ mod test {
         // easy
         let vel_a = Vec2::new(3.0, 4.0); // mag 5.0
         let vel_b = Vec2::new(-3.0, -4.0); // mag 5.0
-        let event = Event::new(Collision::Inside, vel_a, vel_b);
+        let event = Event::new(Vec2::X, vel_a, vel_b);
         assert_eq!(event.intensity, 10.0_f32);
     }
+
+    #[test]
+    fn test_sweep() {
+        use super::*;
+
+        let mover = Transform::from_xyz(0.0, 0.0, 0.0).with_scale((10.0, 10.0, 1.0).into());
+        let collider = Transform::from_xyz(50.0, 0.0, 0.0).with_scale((10.0, 10.0, 1.0).into());
+
+        // Head on, the boxes touch when the mover has moved 40 units
+        let hit = sweep(&mover, Vec2::new(80.0, 0.0), &collider).unwrap();
+        assert_eq!(hit.time, 0.5);
+        assert_eq!(hit.normal, Vec2::NEG_X);
+        assert_eq!(
+            reflect(Vec2::new(3.0, 4.0), hit.normal),
+            Vec2::new(-3.0, 4.0)
+        );
+
+        // Much too far in one step still hits
+        let hit = sweep(&mover, Vec2::new(10_000.0, 0.0), &collider).unwrap();
+        assert_eq!(hit.normal, Vec2::NEG_X);
+
+        // Not far enough
+        assert!(sweep(&mover, Vec2::new(30.0, 0.0), &collider).is_none());
+
+        // Moving away
+        assert!(sweep(&mover, Vec2::new(-80.0, 0.0), &collider).is_none());
+
+        // Passing by
+        assert!(sweep(&mover, Vec2::new(80.0, 80.0), &collider).is_none());
+
+        // Already overlapping
+        let overlapping = Transform::from_xyz(45.0, 0.0, 0.0).with_scale((10.0, 10.0, 1.0).into());
+        assert!(sweep(&overlapping, Vec2::new(80.0, 0.0), &collider).is_none());
+
+        // Coming down onto the top
+        let above = Transform::from_xyz(50.0, 100.0, 0.0).with_scale((10.0, 10.0, 1.0).into());
+        let hit = sweep(&above, Vec2::new(0.0, -200.0), &collider).unwrap();
+        assert_eq!(hit.time, 0.45);
+        assert_eq!(hit.normal, Vec2::Y);
+    }
+
+    #[test]
+    fn test_contact() {
+        use super::*;
+
+        let collider = Transform::from_xyz(0.0, 0.0, 0.0).with_scale((20.0, 100.0, 1.0).into());
+
+        // Slightly into the left side of a tall box
+        let mover = Transform::from_xyz(-12.0, 30.0, 0.0).with_scale((10.0, 10.0, 1.0).into());
+        let contact = contact(&mover, &collider).unwrap();
+        assert_eq!(contact.normal, Vec2::NEG_X);
+        assert_eq!(contact.depth, 3.0);
+        assert_eq!(contact.translation(), Vec2::new(-3.0, 0.0));
+
+        // Slightly into the top
+        let mover = Transform::from_xyz(5.0, 53.0, 0.0).with_scale((10.0, 10.0, 1.0).into());
+        let contact = super::contact(&mover, &collider).unwrap();
+        assert_eq!(contact.normal, Vec2::Y);
+        assert_eq!(contact.depth, 2.0);
+
+        // Just touching isn't overlapping
+        let mover = Transform::from_xyz(15.0, 0.0, 0.0).with_scale((10.0, 10.0, 1.0).into());
+        assert!(super::contact(&mover, &collider).is_none());
+    }
+
+    #[test]
+    fn test_hull_contact() {
+        use super::*;
+
+        let transform = Transform::from_xyz(100.0, 0.0, 0.0);
+
+        // Just grazing the left of a circle
+        let circle = Hull::Circle { radius: 20.0 };
+        let contact = circle
+            .contact(&transform, Vec2::new(75.0, 0.0), 10.0)
+            .unwrap();
+        assert_eq!(contact.normal, Vec2::NEG_X);
+        assert_eq!(contact.depth, 5.0);
+        assert!(circle
+            .contact(&transform, Vec2::new(60.0, 0.0), 10.0)
+            .is_none());
+
+        // Deep inside a square comes out of the nearest side
+        let square = Hull::rectangle(Vec2::new(40.0, 40.0));
+        let contact = square
+            .contact(&transform, Vec2::new(100.0, 15.0), 10.0)
+            .unwrap();
+        assert_eq!(contact.normal, Vec2::Y);
+        assert_eq!(contact.depth, 15.0);
+
+        // Just touching isn't overlapping
+        assert!(square
+            .contact(&transform, Vec2::new(130.0, 0.0), 10.0)
+            .is_none());
+    }
+
+    #[test]
+    fn test_hull_sweep() {
+        use super::*;
+
+        let transform = Transform::from_xyz(100.0, 0.0, 0.0);
+
+        // Head on into a circle
+        let circle = Hull::Circle { radius: 20.0 };
+        let hit = circle
+            .sweep(&transform, Vec2::ZERO, 10.0, Vec2::new(140.0, 0.0))
+            .unwrap();
+        assert_eq!(hit.time, 0.5);
+        assert_eq!(hit.normal, Vec2::NEG_X);
+
+        // Passing above it
+        assert!(circle
+            .sweep(
+                &transform,
+                Vec2::new(0.0, 40.0),
+                10.0,
+                Vec2::new(200.0, 0.0)
+            )
+            .is_none());
+
+        // A square turned into a diamond is hit on its sloped side
+        let diamond = Hull::rectangle(Vec2::new(40.0, 40.0));
+        let turned = transform.with_rotation(bevy::prelude::Quat::from_rotation_z(
+            std::f32::consts::FRAC_PI_4,
+        ));
+        let hit = diamond
+            .sweep(&turned, Vec2::new(0.0, 10.0), 5.0, Vec2::new(200.0, 0.0))
+            .unwrap();
+        assert!(hit.normal.x < 0.0 && hit.normal.y > 0.0);
+        assert!((hit.normal.length() - 1.0).abs() < 1e-5);
+
+        // The unturned square is hit flat on
+        let hit = diamond
+            .sweep(&transform, Vec2::new(0.0, 10.0), 5.0, Vec2::new(200.0, 0.0))
+            .unwrap();
+        assert_eq!(hit.normal, Vec2::NEG_X);
+        assert_eq!(hit.time, 0.375);
+    }
 }
@@ -1,9 +1,198 @@
-use bevy::prelude::{Component, KeyCode, Vec2, Vec3};
+use bevy::{
+    prelude::{Component, Gamepad, KeyCode, Vec2},
+    utils::HashSet,
+};
+use serde::{Deserialize, Serialize};
 
-// TODO: make the kind of controller (keyboard, gamepad, etc) generic
-/// Controls the movement of a paddle with the keyboard. Keybindings are
-/// configurable.
-#[derive(Debug, Clone, Copy, Component)]
+use super::paddle::Side;
+
+/// Something a player can do with their paddle. Every kind of controller is
+/// turned into these, so the game doesn't need to know where they came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Move the paddle up.
+    MoveUp,
+    /// Move the paddle down.
+    MoveDown,
+    /// Move the paddle left.
+    MoveLeft,
+    /// Move the paddle right.
+    MoveRight,
+    /// Serve the ball without waiting for the countdown.
+    Serve,
+    /// Pause or resume the match.
+    Pause,
+}
+
+impl Action {
+    /// Every action, in the order they are shown in menus.
+    pub const ALL: [Self; 6] = [
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::Serve,
+        Self::Pause,
+    ];
+
+    /// The name of the action, as shown in menus.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::MoveUp => "Up",
+            Self::MoveDown => "Down",
+            Self::MoveLeft => "Left",
+            Self::MoveRight => "Right",
+            Self::Serve => "Serve",
+            Self::Pause => "Pause",
+        }
+    }
+
+    /// The unit vector a movement action moves the paddle in. Actions that
+    /// don't move the paddle return `None`.
+    pub const fn direction(&self) -> Option<Vec2> {
+        match self {
+            Self::MoveUp => Some(Vec2::Y),
+            Self::MoveDown => Some(Vec2::NEG_Y),
+            Self::MoveLeft => Some(Vec2::NEG_X),
+            Self::MoveRight => Some(Vec2::X),
+            Self::Serve | Self::Pause => None,
+        }
+    }
+}
+
+/// Where a paddle's actions come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum Controller {
+    /// A set of keys on the keyboard.
+    Keyboard(Keyboard),
+    /// A gamepad's d-pad, left stick and buttons.
+    Gamepad(Gamepad),
+    /// The mouse cursor, which the paddle follows. Clicking serves.
+    Mouse,
+    /// A finger on the paddle's half of a touch screen, which the paddle
+    /// follows.
+    Touch,
+    /// The computer. The paddle's `Ai` component decides what to do.
+    Ai,
+    /// A player on another machine. Their actions arrive as `ActionEvent`s.
+    Network,
+    /// A recording of an earlier match. The recorded actions are played back
+    /// as `ActionEvent`s.
+    Replay,
+}
+
+impl Controller {
+    /// Whether this paddle's actions arrive as `ActionEvent`s instead of
+    /// being read from a local device.
+    pub const fn is_remote(&self) -> bool {
+        matches!(self, Self::Network | Self::Replay)
+    }
+}
+
+impl Default for Controller {
+    fn default() -> Self {
+        Self::Keyboard(Keyboard::default())
+    }
+}
+
+/// What a paddle's controller is asking it to do. Each controller source
+/// fills this in, and the game only ever reads from here.
+#[derive(Debug, Default, Clone, PartialEq, Component)]
+pub struct ActionState {
+    /// The actions being held down right now.
+    pressed: HashSet<Action>,
+    /// The actions that were held down the last time the controller was read.
+    previous: HashSet<Action>,
+    /// Analog movement, from a stick or the computer, on top of the movement
+    /// actions. Each axis goes from -1.0 to 1.0.
+    axis: Vec2,
+    /// A position the paddle should head for, from a mouse or touch. This
+    /// takes over from the movement actions while it is set.
+    target: Option<Vec2>,
+}
+
+impl ActionState {
+    /// Remembers what was pressed, so that `just_pressed` can tell what's new
+    /// the next time the controller is read.
+    pub fn begin_frame(&mut self) {
+        self.previous.clone_from(&self.pressed);
+    }
+
+    /// Lets go of everything.
+    pub fn clear(&mut self) {
+        self.pressed.clear();
+        self.axis = Vec2::ZERO;
+        self.target = None;
+    }
+
+    /// Holds down an action.
+    pub fn press(&mut self, action: Action) {
+        self.pressed.insert(action);
+    }
+
+    /// Lets go of an action.
+    pub fn release(&mut self, action: Action) {
+        self.pressed.remove(&action);
+    }
+
+    /// Sets the analog movement. Each axis is clamped to -1.0 to 1.0.
+    pub fn set_axis(&mut self, axis: Vec2) {
+        self.axis = axis.clamp(Vec2::NEG_ONE, Vec2::ONE);
+    }
+
+    /// Sets the position the paddle should head for, or lets go of it.
+    pub const fn set_target(&mut self, target: Option<Vec2>) {
+        self.target = target;
+    }
+
+    /// Copies what another controller is pressing, keeping track of what was
+    /// pressed before so `just_pressed` still works.
+    pub fn copy_from(&mut self, other: &Self) {
+        self.begin_frame();
+        self.pressed.clone_from(&other.pressed);
+        self.axis = other.axis;
+        self.target = other.target;
+    }
+
+    /// Whether an action is being held down.
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    /// Whether an action has been pressed since the controller was last read.
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.pressed(action) && !self.previous.contains(&action)
+    }
+
+    /// The position the paddle should head for, if any.
+    pub const fn target(&self) -> Option<Vec2> {
+        self.target
+    }
+
+    /// The direction and strength the paddle is being moved in. Movement
+    /// actions and analog movement add up, so holding up and right moves the
+    /// paddle diagonally.
+    pub fn movement(&self) -> Vec2 {
+        self.pressed
+            .iter()
+            .filter_map(Action::direction)
+            .fold(self.axis, |acc, v| acc + v)
+    }
+}
+
+/// Sent to control a paddle whose actions don't come from a local device, like
+/// a player over the network or a replay.
+#[derive(Debug, Clone)]
+pub struct ActionEvent {
+    /// The side of the paddle being controlled. A team's attacker is reached
+    /// through the side it borrows its controls from, see `Player::controls`.
+    pub side: Side,
+    /// What the paddle's controller is asking it to do.
+    pub actions: ActionState,
+}
+
+/// A keyboard layout for controlling a paddle. Keybindings are configurable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Keyboard {
     /// The KeyCode which should move the paddle up.
     pub up: KeyCode,
@@ -13,45 +202,109 @@ pub struct Keyboard {
     pub left: KeyCode,
     /// The KeyCode which should move the paddle right.
     pub right: KeyCode,
+    /// The KeyCode which should serve the ball.
+    pub serve: KeyCode,
+    /// The KeyCode which should pause the match.
+    pub pause: KeyCode,
 }
 
 impl Keyboard {
-    /// Returns the unit vector in the direction of the given key. If this
-    /// key is not one of the controls, returns `None`.
-    pub fn calculate_vec2(&self, k: &KeyCode) -> Option<Vec2> {
-        self.calculate_vec3(k).map(|v| v.truncate())
+    /// The built-in layout for a side, before any keys have been rebound.
+    pub const fn for_side(side: Side) -> Self {
+        match side {
+            Side::Left => wasd(),
+            Side::Right => arrow_keys(),
+            Side::Top => ijkl(),
+            Side::Bottom => numpad(),
+        }
     }
 
-    pub fn calculate_vec3(&self, k: &KeyCode) -> Option<Vec3> {
+    /// Returns the action bound to the given key. If this key is not one of
+    /// the controls, returns `None`.
+    pub fn action(&self, k: &KeyCode) -> Option<Action> {
         match k {
-            k if *k == self.up => Some(Vec3::Y),
-            k if *k == self.down => Some(Vec3::NEG_Y),
-            k if *k == self.left => Some(Vec3::NEG_X),
-            k if *k == self.right => Some(Vec3::X),
+            k if *k == self.up => Some(Action::MoveUp),
+            k if *k == self.down => Some(Action::MoveDown),
+            k if *k == self.left => Some(Action::MoveLeft),
+            k if *k == self.right => Some(Action::MoveRight),
+            k if *k == self.serve => Some(Action::Serve),
+            k if *k == self.pause => Some(Action::Pause),
             // else do nothing
             _ => None,
         }
-        .map(|v| v.normalize())
+    }
+
+    /// Returns the key bound to the given action.
+    pub const fn key(&self, action: Action) -> KeyCode {
+        match action {
+            Action::MoveUp => self.up,
+            Action::MoveDown => self.down,
+            Action::MoveLeft => self.left,
+            Action::MoveRight => self.right,
+            Action::Serve => self.serve,
+            Action::Pause => self.pause,
+        }
+    }
+
+    /// Binds a key to the given action.
+    pub const fn set_key(&mut self, action: Action, key: KeyCode) {
+        let slot = match action {
+            Action::MoveUp => &mut self.up,
+            Action::MoveDown => &mut self.down,
+            Action::MoveLeft => &mut self.left,
+            Action::MoveRight => &mut self.right,
+            Action::Serve => &mut self.serve,
+            Action::Pause => &mut self.pause,
+        };
+        *slot = key;
     }
 }
 
-/// Creates a new `KeyboardControls` with the WASD keys.
+/// Creates a new `Keyboard` layout with the WASD keys.
 pub const fn wasd() -> Keyboard {
     Keyboard {
         up: KeyCode::W,
         down: KeyCode::S,
         left: KeyCode::A,
         right: KeyCode::D,
+        serve: KeyCode::LShift,
+        pause: KeyCode::Escape,
     }
 }
 
-/// Creates a new `KeyboardControls` with the arrow keys.
+/// Creates a new `Keyboard` layout with the arrow keys.
 pub const fn arrow_keys() -> Keyboard {
     Keyboard {
         up: KeyCode::Up,
         down: KeyCode::Down,
         left: KeyCode::Left,
         right: KeyCode::Right,
+        serve: KeyCode::RShift,
+        pause: KeyCode::Escape,
+    }
+}
+
+/// Creates a new `Keyboard` layout with the IJKL keys.
+pub const fn ijkl() -> Keyboard {
+    Keyboard {
+        up: KeyCode::I,
+        down: KeyCode::K,
+        left: KeyCode::J,
+        right: KeyCode::L,
+        serve: KeyCode::U,
+        pause: KeyCode::Escape,
+    }
+}
+
+/// Creates a new `Keyboard` layout with the number pad.
+pub const fn numpad() -> Keyboard {
+    Keyboard {
+        up: KeyCode::Numpad8,
+        down: KeyCode::Numpad5,
+        left: KeyCode::Numpad4,
+        right: KeyCode::Numpad6,
+        serve: KeyCode::Numpad0,
+        pause: KeyCode::Escape,
     }
 }
 
@@ -60,3 +313,41 @@ impl Default for Keyboard {
         arrow_keys()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_keyboard_actions() {
+        let keys = wasd();
+        assert_eq!(keys.action(&KeyCode::W), Some(Action::MoveUp));
+        assert_eq!(keys.action(&KeyCode::Escape), Some(Action::Pause));
+        assert_eq!(keys.action(&KeyCode::Up), None);
+    }
+
+    #[test]
+    fn test_action_state() {
+        let mut state = ActionState::default();
+        state.press(Action::MoveUp);
+        state.press(Action::MoveRight);
+        state.press(Action::Pause);
+
+        // Movement actions blend together
+        assert_eq!(state.movement(), Vec2::new(1.0, 1.0));
+        assert!(state.just_pressed(Action::Pause));
+
+        // Still held on the next frame, so it's no longer new
+        state.begin_frame();
+        assert!(state.pressed(Action::Pause));
+        assert!(!state.just_pressed(Action::Pause));
+
+        // Analog movement adds on top
+        state.begin_frame();
+        state.clear();
+        state.press(Action::MoveDown);
+        state.set_axis(Vec2::new(0.5, 2.0));
+        assert_eq!(state.movement(), Vec2::new(0.5, 0.0));
+        assert!(!state.pressed(Action::Pause));
+    }
+}
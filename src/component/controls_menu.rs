@@ -0,0 +1,90 @@
+use bevy::prelude::Component;
+
+use super::{controls::Action, paddle::Side};
+
+/// A component that identifies the controls menu.
+///
+/// Useful primarily as a parent of other entities that make up the menu, for
+/// easy spawning and despawning. It also keeps track of what the player is
+/// doing in the menu.
+#[derive(Debug, Component)]
+pub struct ControlsMenu {
+    /// The side whose binding is selected.
+    pub side: Side,
+    /// The index into `Action::ALL` of the selected binding.
+    pub row: usize,
+    /// Whether the next key pressed will be bound to the selected action.
+    pub listening: bool,
+    /// What happened the last time a key was bound.
+    pub message: String,
+}
+
+impl ControlsMenu {
+    /// The action whose binding is selected.
+    pub const fn action(&self) -> Action {
+        Action::ALL[self.row]
+    }
+
+    /// Moves the selection up or down by `rows`, wrapping around at either
+    /// end.
+    pub const fn move_selection(&mut self, rows: isize) {
+        let count = Action::ALL.len() as isize;
+        self.row = (self.row as isize + rows).rem_euclid(count) as usize;
+    }
+
+    /// Moves the selection to another side's column, `columns` to the right,
+    /// wrapping around at either end. The selected action stays the same.
+    pub fn move_side(&mut self, columns: isize) {
+        let count = Side::ALL.len() as isize;
+        let column = Side::ALL.iter().position(|&s| s == self.side).unwrap_or(0) as isize;
+        self.side = Side::ALL[(column + columns).rem_euclid(count) as usize];
+    }
+}
+
+impl Default for ControlsMenu {
+    fn default() -> Self {
+        Self {
+            side: Side::Left,
+            row: 0,
+            listening: false,
+            message: String::new(),
+        }
+    }
+}
+
+/// Identifies the text showing the key bound to an action.
+#[derive(Debug, Component)]
+pub struct BindingText {
+    /// The side the binding belongs to.
+    pub side: Side,
+    /// The action the key is bound to.
+    pub action: Action,
+}
+
+/// Identifies the text telling the player what happened when they bound a key.
+#[derive(Debug, Default, Component)]
+pub struct ControlsMessage;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_selection_wraps_around() {
+        let mut menu = ControlsMenu::default();
+        menu.move_selection(-1);
+        assert_eq!(menu.action(), Action::Pause);
+        menu.move_selection(2);
+        assert_eq!(menu.action(), Action::MoveDown);
+    }
+
+    #[test]
+    fn test_side_wraps_around() {
+        let mut menu = ControlsMenu::default();
+        menu.move_side(-1);
+        assert_eq!(menu.side, Side::Bottom);
+        menu.move_side(2);
+        assert_eq!(menu.side, Side::Right);
+        assert_eq!(menu.action(), Action::MoveUp);
+    }
+}
@@ -0,0 +1,7 @@
+use bevy::prelude::Component;
+
+/// A component that identifies the results screen shown when a match is over.
+/// Useful primarily as a parent of the entities that make up the screen, for
+/// easy spawning and despawning.
+#[derive(Debug, Default, Component)]
+pub struct GameOverScreen;
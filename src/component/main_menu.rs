@@ -5,3 +5,20 @@ use bevy::prelude::Component;
 /// despawning.
 #[derive(Debug, Default, Component)]
 pub struct MainMenu;
+
+/// Identifies the main menu text that shows who the player is up against.
+#[derive(Debug, Default, Component)]
+pub struct OpponentText;
+
+/// Identifies the main menu text that shows which arena will be played.
+#[derive(Debug, Default, Component)]
+pub struct ArenaText;
+
+/// Identifies the main menu text that shows whether multiball mode is on.
+#[derive(Debug, Default, Component)]
+pub struct MultiballText;
+
+/// Identifies the main menu text that shows who plays alongside the left
+/// player in doubles.
+#[derive(Debug, Default, Component)]
+pub struct TeammateText;
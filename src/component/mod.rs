@@ -1,17 +1,35 @@
+/// Component for computer-controlled paddles.
+pub mod ai;
 /// Components and bundles for the ball.
 pub mod ball;
 /// Components and bundles for bounding boxes.
 pub mod bounding_box;
+/// Components and bundles for Breakout bricks.
+pub mod brick;
 /// Components and Events for things that can collide.
 pub mod collider;
 /// Components and bundles for the controls.
 pub mod controls;
+/// Components for the controls menu.
+pub mod controls_menu;
 pub mod game;
+/// Components for the results screen.
+pub mod game_over;
 pub mod main_menu;
+/// Components and bundles for obstacles in the arena.
+pub mod obstacle;
 /// Components and bundles for the paddles.
 pub mod paddle;
+/// Components for the pause menu.
+pub mod pause_menu;
+/// Components and bundles for power-ups and their effects.
+pub mod power_up;
 /// Components and bundles for displaying the score.
 pub mod score;
+/// Components and bundles for serving the ball.
+pub mod serve;
+/// Component for spin.
+pub mod spin;
 /// Component for velocity.
 pub mod velocity;
 /// Components and bundles for the walls.
@@ -0,0 +1,272 @@
+use bevy::{
+    prelude::{Bundle as BevyBundle, Color, Component, Quat, Transform, Vec2},
+    time::{Timer, TimerMode},
+};
+use bevy_prototype_lyon::{
+    prelude::{Fill, GeometryBuilder, ShapeBundle},
+    shapes::{self, RectangleOrigin},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    collider::{Collider, Hull},
+    velocity::Velocity,
+};
+
+/// A component that is used to mark an entity as being an obstacle in the
+/// arena.
+#[derive(Debug, Component, Default)]
+pub struct Obstacle;
+
+/// The outline of an obstacle, in the obstacle's own space.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Shape {
+    /// A rectangle with the given width and height.
+    Rectangle(Vec2),
+    /// A circle with the given radius.
+    Circle(f32),
+    /// A closed polygon, given by its corners in order.
+    Polygon(Vec<Vec2>),
+}
+
+impl Shape {
+    /// The outline the ball bounces off.
+    pub fn hull(&self) -> Hull {
+        match self {
+            Self::Rectangle(size) => Hull::rectangle(*size),
+            Self::Circle(radius) => Hull::Circle { radius: *radius },
+            Self::Polygon(points) => Hull::Polygon(points.clone()),
+        }
+    }
+
+    /// Whether the shape encloses any area.
+    pub fn has_area(&self) -> bool {
+        match self {
+            Self::Rectangle(size) => size.x > 0.0 && size.y > 0.0,
+            Self::Circle(radius) => *radius > 0.0,
+            Self::Polygon(points) => points.len() >= 3,
+        }
+    }
+
+    fn geometry(&self) -> GeometryBuilder {
+        match self {
+            Self::Rectangle(size) => GeometryBuilder::new().add(&shapes::Rectangle {
+                extents: *size,
+                origin: RectangleOrigin::Center,
+            }),
+            Self::Circle(radius) => GeometryBuilder::new().add(&shapes::Circle {
+                radius: *radius,
+                center: Vec2::ZERO,
+            }),
+            Self::Polygon(points) => GeometryBuilder::new().add(&shapes::Polygon {
+                points: points.clone(),
+                closed: true,
+            }),
+        }
+    }
+}
+
+/// Moves an obstacle through a loop of points at a steady speed.
+#[derive(Debug, Clone, Component, Default, PartialEq)]
+pub struct Waypoints {
+    /// The points to visit, in order. After the last point the obstacle heads
+    /// back to the first.
+    pub points: Vec<Vec2>,
+    /// The position in `points` of the point the obstacle is heading for.
+    pub next: usize,
+    /// How fast the obstacle moves, in units per second.
+    pub speed: f32,
+}
+
+impl Waypoints {
+    /// Moves `position` up to `distance` along the path, turning at each
+    /// point it reaches.
+    pub fn advance(&mut self, mut position: Vec2, mut distance: f32) -> Vec2 {
+        // Stop if a whole lap goes nowhere, so a path whose points are all in
+        // the same place can't turn forever
+        let mut stuck = 0;
+        while !self.points.is_empty() && stuck <= self.points.len() {
+            let target = self.points[self.next];
+            let to_target = position.distance(target);
+            if to_target > distance {
+                return position + (target - position) / to_target * distance;
+            }
+            stuck = if to_target > 0.0 { 0 } else { stuck + 1 };
+            position = target;
+            distance -= to_target;
+            self.next = (self.next + 1) % self.points.len();
+        }
+        position
+    }
+}
+
+/// Spins an obstacle about its centre, in radians per second.
+#[derive(Debug, Clone, Copy, Component, Default, PartialEq)]
+pub struct Rotating(pub f32);
+
+/// Removes an obstacle once its time is up.
+#[derive(Debug, Clone, Component)]
+pub struct Lifetime(pub Timer);
+
+impl Lifetime {
+    /// An obstacle that lasts for the given number of seconds.
+    pub fn from_seconds(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+}
+
+/// How to build an obstacle, as it is written in an arena file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Blueprint {
+    /// The outline of the obstacle.
+    pub shape: Shape,
+    /// Where the obstacle starts.
+    pub position: Vec2,
+    /// How far the obstacle is turned, in radians.
+    #[serde(default)]
+    pub rotation: f32,
+    /// How fast the obstacle spins, in radians per second.
+    #[serde(default)]
+    pub spin: f32,
+    /// Points the obstacle moves through after leaving its starting position,
+    /// before heading back to it.
+    #[serde(default)]
+    pub path: Vec<Vec2>,
+    /// How fast the obstacle moves along its path, in units per second.
+    #[serde(default)]
+    pub speed: f32,
+    /// How many seconds the obstacle stays for. Obstacles without a lifetime
+    /// stay for the rest of the game.
+    #[serde(default)]
+    pub lifetime: Option<f32>,
+}
+
+impl Blueprint {
+    /// A bundle for the obstacle, without its lifetime.
+    pub fn bundle(&self) -> Bundle {
+        let mut points = self.path.clone();
+        if !points.is_empty() {
+            points.push(self.position);
+        }
+
+        Bundle {
+            shape: ShapeBundle {
+                path: self.shape.geometry().build(),
+                transform: Transform::from_translation(self.position.extend(0.0))
+                    .with_rotation(Quat::from_rotation_z(self.rotation)),
+                ..Default::default()
+            },
+            fill: Fill::color(Color::GRAY),
+            hull: self.shape.hull(),
+            waypoints: Waypoints {
+                points,
+                next: 0,
+                speed: self.speed,
+            },
+            rotating: Rotating(self.spin),
+            ..Default::default()
+        }
+    }
+
+    /// How long the obstacle stays for, if it doesn't stay for the rest of
+    /// the game.
+    pub fn lifetime(&self) -> Option<Lifetime> {
+        self.lifetime.map(Lifetime::from_seconds)
+    }
+}
+
+/// A bundle that creates an obstacle the ball bounces off.
+#[derive(BevyBundle)]
+pub struct Bundle {
+    #[bundle]
+    /// Controls the look and position of the obstacle.
+    shape: ShapeBundle,
+    /// Controls the color of the obstacle.
+    fill: Fill,
+    collider: Collider,
+    /// The outline the ball bounces off.
+    pub hull: Hull,
+    /// How fast the obstacle is moving along its path.
+    pub velocity: Velocity,
+    /// The path the obstacle moves along, if any.
+    pub waypoints: Waypoints,
+    /// How fast the obstacle spins.
+    pub rotating: Rotating,
+    obstacle: Obstacle,
+}
+
+impl Default for Bundle {
+    /// By default, obstacles are small grey squares that sit still at the
+    /// origin.
+    fn default() -> Self {
+        let shape = Shape::Rectangle(Vec2::new(20.0, 20.0));
+        Self {
+            shape: ShapeBundle {
+                path: shape.geometry().build(),
+                ..Default::default()
+            },
+            fill: Fill::color(Color::GRAY),
+            collider: Collider,
+            hull: shape.hull(),
+            velocity: Velocity::default(),
+            waypoints: Waypoints::default(),
+            rotating: Rotating::default(),
+            obstacle: Obstacle,
+        }
+    }
+}
+
+/// Spawns an obstacle every so often, for as long as the game goes on.
+#[derive(Debug, Clone, Component)]
+pub struct Spawner {
+    /// Counts down to the next obstacle.
+    pub timer: Timer,
+    /// The obstacle to spawn.
+    pub blueprint: Blueprint,
+}
+
+impl Spawner {
+    /// Spawns the obstacle every `seconds` seconds.
+    pub fn every(seconds: f32, blueprint: Blueprint) -> Self {
+        Self {
+            timer: Timer::from_seconds(seconds, TimerMode::Repeating),
+            blueprint,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_waypoints_loop() {
+        let mut waypoints = Waypoints {
+            points: vec![Vec2::new(10.0, 0.0), Vec2::ZERO],
+            next: 0,
+            speed: 1.0,
+        };
+
+        // Partway to the first point
+        assert_eq!(waypoints.advance(Vec2::ZERO, 4.0), Vec2::new(4.0, 0.0));
+        assert_eq!(waypoints.next, 0);
+
+        // Past the first point and back along the way it came
+        assert_eq!(
+            waypoints.advance(Vec2::new(4.0, 0.0), 8.0),
+            Vec2::new(8.0, 0.0)
+        );
+        assert_eq!(waypoints.next, 1);
+
+        // All the way round
+        assert_eq!(
+            waypoints.advance(Vec2::new(8.0, 0.0), 20.0),
+            Vec2::new(8.0, 0.0)
+        );
+        assert_eq!(waypoints.next, 1);
+
+        // Nowhere to go
+        let mut still = Waypoints::default();
+        assert_eq!(still.advance(Vec2::ONE, 5.0), Vec2::ONE);
+    }
+}
@@ -1,33 +1,62 @@
 use bevy::{
     prelude::{Bundle as BevyBundle, Color, Component, Transform, Vec2, Vec3},
-    reflect::Reflect,
+    reflect::{FromReflect, Reflect},
     sprite::{Sprite, SpriteBundle},
 };
+use serde::{Deserialize, Serialize};
 
-use crate::constants::{PADDLE_DEFAULT_FRICTION, PADDLE_SCALE};
+use crate::{
+    constants::{PADDLE_DEFAULT_FRICTION, PADDLE_SCALE, PADDLE_SPEED_MULTIPLIER},
+    resources::Bindings,
+};
 
 use super::{
     collider::Collider,
-    controls::{self, Keyboard},
-    velocity::{Friction, Velocity},
+    controls::{ActionState, Controller, Keyboard},
+    velocity::{Friction, MaxSpeed, Velocity},
 };
 
 /// A side of the screen. Used mainly for identifying who scored.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect, FromReflect, Serialize, Deserialize)]
 pub enum Side {
     /// The left side of the screen.
     Left,
     /// The right side of the screen.
     Right,
+    /// The top of the screen, in four-player arenas.
+    Top,
+    /// The bottom of the screen, in four-player arenas.
+    Bottom,
 }
 
 impl Side {
+    /// Every side, in the order players are numbered.
+    pub const ALL: [Self; 4] = [Self::Left, Self::Right, Self::Top, Self::Bottom];
+
     #[must_use]
     /// Returns the opposite side of the screen.
     pub const fn opposite(&self) -> Self {
         match self {
             Self::Left => Self::Right,
             Self::Right => Self::Left,
+            Self::Top => Self::Bottom,
+            Self::Bottom => Self::Top,
+        }
+    }
+
+    /// Whether paddles on this side move up and down, rather than left and
+    /// right.
+    pub const fn moves_vertically(&self) -> bool {
+        matches!(self, Self::Left | Self::Right)
+    }
+
+    /// The direction from this side of the screen toward the middle.
+    pub const fn inward(&self) -> Vec2 {
+        match self {
+            Self::Left => Vec2::X,
+            Self::Right => Vec2::NEG_X,
+            Self::Top => Vec2::NEG_Y,
+            Self::Bottom => Vec2::Y,
         }
     }
 }
@@ -39,20 +68,58 @@ impl Default for Side {
     }
 }
 
+/// Where a paddle plays in its team, when a side has more than one paddle.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum Role {
+    /// Plays at the back, in front of the goal. A side's only paddle is its
+    /// defender.
+    #[default]
+    Defender,
+    /// Plays further forward, toward the middle of the court.
+    Attacker,
+}
+
 /// A component that records a Player's side, their score, and their starting
-/// position.
+/// position. Every paddle on a side plays for the same team.
 #[derive(Component, Clone, Reflect)]
 pub struct Player {
-    /// The side of the screen the player is on.
+    /// The side of the screen the player is on, which is also their team.
     pub side: Side,
+    /// Where the player plays in their team.
+    pub role: Role,
     /// The starting position of the player.
     pub starting_pos: Vec2,
+    /// The side whose keys and gamepad play this paddle, when it isn't the
+    /// usual one for its side and role.
+    pub seat: Option<Side>,
 }
 
 impl Player {
     /// Creates a new player on the given side, at the given starting position.
     pub const fn new(side: Side, starting_pos: Vec2) -> Self {
-        Self { side, starting_pos }
+        Self {
+            side,
+            role: Role::Defender,
+            starting_pos,
+            seat: None,
+        }
+    }
+
+    /// The side whose keys and gamepad play this paddle. A defender is played
+    /// with its own side's, and an attacker borrows those of a side that has
+    /// no paddle when two teams play doubles. A paddle with a seat is played
+    /// with that side's, wherever it is.
+    pub const fn controls(&self) -> Side {
+        if let Some(seat) = self.seat {
+            return seat;
+        }
+        match (self.role, self.side) {
+            (Role::Defender, side) => side,
+            (Role::Attacker, Side::Left) => Side::Top,
+            (Role::Attacker, Side::Right) => Side::Bottom,
+            (Role::Attacker, Side::Top) => Side::Left,
+            (Role::Attacker, Side::Bottom) => Side::Right,
+        }
     }
 }
 
@@ -60,50 +127,103 @@ impl Default for Player {
     fn default() -> Self {
         Self {
             side: Side::Left,
+            role: Role::Defender,
             starting_pos: Vec2::new(0.0, 0.0),
+            seat: None,
         }
     }
 }
 
-/// A bundle that contains all the components needed to create a paddle. Includes
-/// a `SpriteBundle` for visual appearance and position, a `KeyboardControls` for
-/// input, is a Collider, has a Velocity, and includes a Player component.
+/// A bundle that contains all the components needed to create a paddle.
+///
+/// Includes a `SpriteBundle` for visual appearance and position, a
+/// `Controller` and `ActionState` for input, is a Collider, has a Velocity,
+/// and includes a Player component.
 #[derive(BevyBundle)]
 pub struct Bundle {
     #[bundle]
     /// Controls the position and look of the paddle.
     pub sprite: SpriteBundle,
-    /// Defines the input controls for the paddle. Used to segregate controls
+    /// Defines where the paddle's input comes from. Used to segregate controls
     /// per player.
-    pub controls: Keyboard,
+    pub controller: Controller,
+    /// What the paddle's controller is currently asking it to do.
+    pub actions: ActionState,
     /// Identifies the paddle as something that can be collided with.
     pub collider: Collider,
     /// Defines the velocity of the paddle.
     pub velocity: Velocity,
     /// Defines the rate at which the paddle comes to a stop when not being moved.
     pub friction: Friction,
+    /// Defines the fastest the paddle can be moved.
+    pub max_speed: MaxSpeed,
     /// Identifies the paddle as a player.
     pub player: Player,
 }
 
 impl Bundle {
-    /// Creates a new paddle bundle with the given controls and side.
-    pub fn new(controls: Keyboard, side: Side) -> Self {
-        Self {
-            controls,
+    /// Creates a new paddle bundle with the given controller and side. Paddles
+    /// on the top and bottom lie flat.
+    pub fn new(controller: Controller, side: Side) -> Self {
+        let bundle = Self {
+            controller,
             player: Player::new(side, Vec2::new(0.0, 0.0)),
             ..Default::default()
+        };
+        if side.moves_vertically() {
+            bundle
+        } else {
+            bundle.with_dimensions(PADDLE_SCALE.y, PADDLE_SCALE.x)
         }
     }
 
+    /// Creates a new paddle bundle for the player on `side`, played with that
+    /// side's built-in keys.
+    pub fn for_side(side: Side) -> Self {
+        Self::new(Controller::Keyboard(Keyboard::for_side(side)), side)
+    }
+
     /// Creates a new paddle bundle for the left player.
     pub fn left_player() -> Self {
-        Self::new(controls::wasd(), Side::Left)
+        Self::for_side(Side::Left)
     }
 
     /// Creates a new paddle bundle for the right player.
     pub fn right_player() -> Self {
-        Self::new(controls::arrow_keys(), Side::Right)
+        Self::for_side(Side::Right)
+    }
+
+    #[must_use]
+    /// Sets where the paddle plays in its team. A keyboard-controlled paddle
+    /// switches to the built-in keys for its role.
+    pub const fn with_role(mut self, role: Role) -> Self {
+        self.player.role = role;
+        if let Controller::Keyboard(_) = self.controller {
+            self.controller = Controller::Keyboard(Keyboard::for_side(self.player.controls()));
+        }
+        self
+    }
+
+    #[must_use]
+    /// Plays the paddle with the keys and gamepad of the player on `seat`,
+    /// wherever the paddle is. A keyboard-controlled paddle switches to that
+    /// side's built-in keys.
+    pub const fn with_seat(mut self, seat: Side) -> Self {
+        self.player.seat = Some(seat);
+        if let Controller::Keyboard(_) = self.controller {
+            self.controller = Controller::Keyboard(Keyboard::for_side(seat));
+        }
+        self
+    }
+
+    #[must_use]
+    /// Uses the player's own keys for this paddle, if it is played with the
+    /// keyboard.
+    pub const fn with_bindings(mut self, bindings: &Bindings) -> Self {
+        if let Controller::Keyboard(_) = self.controller {
+            self.controller = Controller::Keyboard(bindings.get(self.player.controls()));
+        }
+        self
     }
 
     #[must_use]
@@ -120,6 +240,13 @@ impl Bundle {
         self
     }
 
+    #[must_use]
+    /// Sets the fastest the paddle can be moved.
+    pub const fn with_max_speed(mut self, max_speed: MaxSpeed) -> Self {
+        self.max_speed = max_speed;
+        self
+    }
+
     #[must_use]
     pub const fn with_dimensions(mut self, width: f32, height: f32) -> Self {
         self.sprite.transform.scale.x = width;
@@ -145,11 +272,13 @@ impl Default for Bundle {
                 },
                 ..Default::default()
             },
-            controls: Keyboard::default(),
-            collider: Collider::default(),
+            controller: Controller::default(),
+            actions: ActionState::default(),
+            collider: Collider,
             player: Player::default(),
             velocity: Velocity::default(),
             friction: Friction(PADDLE_DEFAULT_FRICTION),
+            max_speed: MaxSpeed(PADDLE_SPEED_MULTIPLIER),
         }
     }
 }
@@ -0,0 +1,7 @@
+use bevy::prelude::Component;
+
+/// A component that identifies the pause menu overlay. Useful primarily as a
+/// parent of other entities that make up the overlay, for easy spawning and
+/// despawning.
+#[derive(Debug, Default, Component)]
+pub struct PauseMenu;
@@ -0,0 +1,227 @@
+use bevy::{
+    prelude::{Bundle as BevyBundle, Color, Component, Entity, Transform, Vec2},
+    time::{Timer, TimerMode},
+};
+use bevy_prototype_lyon::{
+    prelude::{Fill, GeometryBuilder, ShapeBundle, Stroke},
+    shapes,
+};
+
+use crate::{arena::Area, constants::POWER_UP_SCALE};
+
+use super::paddle::Side;
+
+/// A kind of power-up, and the effect it has once it is collected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// Makes the collector's paddle taller.
+    Enlarge,
+    /// Makes the opponent's paddle shorter.
+    Shrink,
+    /// Makes the ball move faster.
+    SpeedUp,
+    /// Makes the ball move slower.
+    SlowDown,
+    /// Puts more balls into play.
+    Multiball,
+    /// Makes the collector's paddle catch the ball and hold on to it until
+    /// they serve it back.
+    Sticky,
+    /// Swaps the opponent's up and down.
+    Reverse,
+}
+
+/// What a power-up's effect is applied to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// The paddles of whoever collected it.
+    OwnPaddles,
+    /// The paddles of every other side.
+    OpponentPaddles,
+    /// Every ball in play.
+    Balls,
+}
+
+/// The part of a paddle or ball an effect changes. Only one effect at a time
+/// can change each part of something, so a newer effect replaces an older one
+/// in the same slot instead of piling on top of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slot {
+    /// A paddle's height.
+    Size,
+    /// The ball's pace.
+    Speed,
+    /// How many balls are in play.
+    Balls,
+    /// Whether a paddle catches the ball.
+    Sticky,
+    /// Which way a paddle's controls go.
+    Controls,
+}
+
+impl Kind {
+    /// Every kind of power-up.
+    pub const ALL: [Self; 7] = [
+        Self::Enlarge,
+        Self::Shrink,
+        Self::SpeedUp,
+        Self::SlowDown,
+        Self::Multiball,
+        Self::Sticky,
+        Self::Reverse,
+    ];
+
+    /// What the effect is applied to.
+    pub const fn target(&self) -> Target {
+        match self {
+            Self::Enlarge | Self::Sticky => Target::OwnPaddles,
+            Self::Shrink | Self::Reverse => Target::OpponentPaddles,
+            Self::SpeedUp | Self::SlowDown | Self::Multiball => Target::Balls,
+        }
+    }
+
+    /// The part of its target the effect changes.
+    pub const fn slot(&self) -> Slot {
+        match self {
+            Self::Enlarge | Self::Shrink => Slot::Size,
+            Self::SpeedUp | Self::SlowDown => Slot::Speed,
+            Self::Multiball => Slot::Balls,
+            Self::Sticky => Slot::Sticky,
+            Self::Reverse => Slot::Controls,
+        }
+    }
+
+    /// A short name for the HUD.
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::Enlarge => "BIG",
+            Self::Shrink => "SMALL",
+            Self::SpeedUp => "FAST",
+            Self::SlowDown => "SLOW",
+            Self::Multiball => "MULTI",
+            Self::Sticky => "STICKY",
+            Self::Reverse => "REVERSE",
+        }
+    }
+
+    /// The color the power-up and its HUD icon are shown in.
+    pub const fn color(&self) -> Color {
+        match self {
+            Self::Enlarge => Color::GREEN,
+            Self::Shrink => Color::ORANGE_RED,
+            Self::SpeedUp => Color::YELLOW,
+            Self::SlowDown => Color::CYAN,
+            Self::Multiball => Color::WHITE,
+            Self::Sticky => Color::PURPLE,
+            Self::Reverse => Color::PINK,
+        }
+    }
+}
+
+/// A power-up waiting on the court for the ball to pass through it.
+#[derive(Component, Debug, Clone)]
+pub struct PowerUp {
+    /// What the power-up does.
+    pub kind: Kind,
+    /// Counts down to the power-up disappearing if nobody collects it.
+    pub expires: Timer,
+}
+
+/// A bundle that creates a power-up on the court.
+#[derive(BevyBundle)]
+pub struct Bundle {
+    #[bundle]
+    /// Controls the look and position of the power-up.
+    shape: ShapeBundle,
+    /// Controls the color of the power-up.
+    fill: Fill,
+    /// Outlines the power-up.
+    stroke: Stroke,
+    /// What the power-up does.
+    pub power_up: PowerUp,
+}
+
+impl Bundle {
+    /// A power-up of the given kind at the given position, which disappears
+    /// after `lifetime` seconds if nobody collects it.
+    pub fn new(kind: Kind, position: Vec2, lifetime: f32) -> Self {
+        Self {
+            shape: ShapeBundle {
+                path: GeometryBuilder::build_as(&shapes::Circle::default()),
+                transform: Transform {
+                    translation: position.extend(0.0),
+                    scale: POWER_UP_SCALE,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            fill: Fill::color(kind.color().with_a(0.6)),
+            stroke: Stroke::new(kind.color(), 0.1),
+            power_up: PowerUp {
+                kind,
+                expires: Timer::from_seconds(lifetime, TimerMode::Once),
+            },
+        }
+    }
+}
+
+/// Puts power-ups on the court every so often, for as long as the game goes
+/// on.
+#[derive(Component, Debug, Clone)]
+pub struct Spawner {
+    /// Counts down to the next power-up.
+    pub timer: Timer,
+    /// Where on the court power-ups turn up.
+    pub area: Area,
+}
+
+impl Spawner {
+    /// Puts a power-up somewhere in `area` every `seconds` seconds.
+    pub fn every(seconds: f32, area: Area) -> Self {
+        Self {
+            timer: Timer::from_seconds(seconds, TimerMode::Repeating),
+            area,
+        }
+    }
+}
+
+/// What something was like before an effect changed it, so it can be put back
+/// when the effect wears off.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Original {
+    /// A paddle's length.
+    Length(f32),
+    /// The ball's speed factor.
+    SpeedFactor(f32),
+    /// The effect only added a component, which is removed again.
+    Nothing,
+}
+
+/// An effect from a collected power-up, for as long as it lasts.
+#[derive(Component, Debug, Clone)]
+pub struct Effect {
+    /// The kind of power-up the effect came from.
+    pub kind: Kind,
+    /// The side that collected the power-up.
+    pub owner: Side,
+    /// Counts down to the effect wearing off.
+    pub timer: Timer,
+    /// Everything the effect changed, and what each one was like before.
+    pub targets: Vec<(Entity, Original)>,
+}
+
+impl Effect {
+    /// Whole seconds left before the effect wears off, rounded up, for the
+    /// HUD.
+    pub fn seconds_left(&self) -> u32 {
+        self.timer.remaining().as_secs_f32().ceil() as u32
+    }
+}
+
+/// Makes a paddle catch the ball and hold on to it.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Sticky;
+
+/// Swaps a paddle's up and down, and its left and right.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Reversed;
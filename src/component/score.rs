@@ -1,3 +1,5 @@
+use std::fmt::{self, Display, Formatter};
+
 use bevy::prelude::{Bundle as BevyBundle, Component, Vec2};
 use bevy::text::{Text, Text2dBundle, TextAlignment, TextStyle};
 
@@ -22,6 +24,13 @@ impl Bundle {
         self
     }
 
+    /// Sets the score the player starts with. Set before the style, so that
+    /// the text shows it.
+    pub const fn with_value(mut self, value: u64) -> Self {
+        self.score.value = value;
+        self
+    }
+
     pub const fn at(mut self, position: Vec2) -> Self {
         self.text.transform.translation = position.extend(0.0);
         self
@@ -52,13 +61,23 @@ impl Score {
         Self { value, side }
     }
 
-    pub fn increment(&mut self) {
+    pub const fn increment(&mut self) {
         self.value += 1;
     }
+
+    /// Takes a point, or a life, away. Never goes below zero.
+    pub const fn decrement(&mut self) {
+        self.value = self.value.saturating_sub(1);
+    }
 }
 
-impl ToString for Score {
-    fn to_string(&self) -> String {
-        self.value.to_string()
+/// Identifies the text that shows the score, lives and level during a game
+/// of Breakout.
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct BreakoutText;
+
+impl Display for Score {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
     }
 }
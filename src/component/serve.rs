@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use bevy::{
+    prelude::{Bundle as BevyBundle, Component, Vec2},
+    text::{Text, Text2dBundle, TextAlignment, TextStyle},
+    time::{Timer, TimerMode},
+};
+
+use super::paddle::Side;
+
+/// Holds the ball in place until it is served. Once the countdown finishes,
+/// the ball is launched toward the receiving side and this is removed.
+#[derive(Component, Debug, Clone)]
+pub struct Serving {
+    /// Counts down to the serve.
+    pub countdown: Timer,
+    /// The side the ball will be served toward.
+    pub toward: Side,
+}
+
+impl Serving {
+    /// Serves toward `toward` after `countdown` seconds.
+    pub fn new(toward: Side, countdown: f32) -> Self {
+        Self {
+            countdown: Timer::from_seconds(countdown, TimerMode::Once),
+            toward,
+        }
+    }
+
+    /// Whole seconds left before the serve, rounded up, for display.
+    pub fn seconds_left(&self) -> u32 {
+        let left = self.countdown.duration() - self.countdown.elapsed();
+        left.as_secs() as u32 + u32::from(left.subsec_nanos() > 0)
+    }
+
+    /// Advances the countdown. Returns true once it's time to serve.
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        self.countdown.tick(delta).finished()
+    }
+}
+
+/// Identifies the text that shows the countdown to the next serve.
+#[derive(Component, Default, Debug, Clone, Copy)]
+pub struct CountdownText;
+
+/// A bundle for the text that shows the countdown to the next serve.
+#[derive(BevyBundle, Clone)]
+pub struct CountdownBundle {
+    pub marker: CountdownText,
+    #[bundle]
+    pub text: Text2dBundle,
+}
+
+impl CountdownBundle {
+    #[must_use]
+    /// Sets the style of the countdown text.
+    pub fn with_style(mut self, style: TextStyle) -> Self {
+        self.text.text = Text::from_section("", style).with_alignment(TextAlignment::Center);
+        self
+    }
+
+    #[must_use]
+    /// Sets the position of the countdown text.
+    pub const fn at(mut self, position: Vec2) -> Self {
+        self.text.transform.translation = position.extend(1.0);
+        self
+    }
+}
+
+impl Default for CountdownBundle {
+    fn default() -> Self {
+        Self {
+            marker: CountdownText,
+            text: Text2dBundle {
+                text: Text::from_section("", TextStyle::default())
+                    .with_alignment(TextAlignment::Center),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_countdown() {
+        let mut serving = Serving::new(Side::Left, 3.0);
+        assert_eq!(serving.seconds_left(), 3);
+
+        assert!(!serving.tick(Duration::from_millis(500)));
+        assert_eq!(serving.seconds_left(), 3);
+
+        assert!(!serving.tick(Duration::from_millis(1000)));
+        assert_eq!(serving.seconds_left(), 2);
+
+        assert!(serving.tick(Duration::from_millis(1500)));
+        assert_eq!(serving.seconds_left(), 0);
+    }
+}
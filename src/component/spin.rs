@@ -0,0 +1,109 @@
+use bevy::prelude::{Component, Deref, DerefMut, Vec2};
+
+use crate::constants::{MAGNUS_FACTOR, MAX_SPIN, SPIN_DECAY, SPIN_TRANSFER, WALL_SPIN_GRIP};
+
+/// How fast an entity is spinning, in radians per second. Positive values spin
+/// counterclockwise.
+#[derive(Debug, Default, Clone, Copy, Deref, DerefMut, Component, PartialEq)]
+pub struct Spin(pub f32);
+
+impl Spin {
+    /// Adds spin from a surface moving past the ball at the point of contact,
+    /// like a paddle being swept across the ball as it is hit. `normal` is the
+    /// normal of the surface.
+    pub fn impart(&mut self, normal: Vec2, surface_vel: Vec2) {
+        // The contact point is on the opposite side of the ball from the
+        // normal, so the surface drags the ball around its center from there
+        self.0 = SPIN_TRANSFER
+            .mul_add((-normal).perp_dot(surface_vel), self.0)
+            .clamp(-MAX_SPIN, MAX_SPIN);
+    }
+
+    /// Curves a velocity over `dt` seconds, like the Magnus effect. The
+    /// velocity turns in the direction of the spin and keeps its speed.
+    pub fn curve(&self, velocity: Vec2, dt: f32) -> Vec2 {
+        Vec2::from_angle(self.0 * MAGNUS_FACTOR * dt).rotate(velocity)
+    }
+
+    /// Bleeds off spin over `dt` seconds.
+    pub fn decay(&mut self, dt: f32) {
+        self.0 *= (-SPIN_DECAY * dt).exp();
+    }
+
+    /// Bounces a ball of the given radius off a surface that doesn't move, like
+    /// a wall. `velocity` is the velocity after it has been reflected. The
+    /// surface grips the ball, turning some of the spin into sideways speed,
+    /// while the ball keeps its overall speed.
+    pub fn bounce(&mut self, velocity: Vec2, normal: Vec2, radius: f32) -> Vec2 {
+        let grip = normal.perp() * self.0 * radius * WALL_SPIN_GRIP;
+        self.0 *= 1.0 - WALL_SPIN_GRIP;
+
+        (velocity + grip).normalize_or_zero() * velocity.length()
+    }
+}
+
+impl From<f32> for Spin {
+    fn from(spin: f32) -> Self {
+        Self(spin)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_impart_spin() {
+        // A paddle on the left moving up past the ball spins it clockwise
+        let mut spin = Spin::default();
+        spin.impart(Vec2::X, Vec2::new(0.0, 40.0));
+        assert!(spin.0 < 0.0);
+
+        // And moving down spins it counterclockwise
+        let mut spin = Spin::default();
+        spin.impart(Vec2::X, Vec2::new(0.0, -40.0));
+        assert!(spin.0 > 0.0);
+
+        // Moving straight into the ball doesn't spin it
+        let mut spin = Spin::default();
+        spin.impart(Vec2::X, Vec2::new(40.0, 0.0));
+        assert_eq!(spin.0, 0.0);
+
+        // There's a limit to how fast it can spin
+        let mut spin = Spin::default();
+        spin.impart(Vec2::X, Vec2::new(0.0, 1_000_000.0));
+        assert_eq!(spin.0, -MAX_SPIN);
+    }
+
+    #[test]
+    fn test_curve() {
+        let velocity = Vec2::new(50.0, 0.0);
+
+        // Counterclockwise spin curves a ball moving right upwards
+        let curved = Spin(2.0).curve(velocity, 0.5);
+        assert!(curved.y > 0.0);
+        assert!((curved.length() - velocity.length()).abs() < 1e-3);
+
+        // No spin, no curve
+        assert_eq!(Spin(0.0).curve(velocity, 0.5), velocity);
+    }
+
+    #[test]
+    fn test_decay() {
+        let mut spin = Spin(2.0);
+        spin.decay(1.0);
+        assert!(spin.0 > 0.0 && spin.0 < 2.0);
+    }
+
+    #[test]
+    fn test_bounce() {
+        // Coming down onto a floor, moving right, spinning counterclockwise
+        let mut spin = Spin(2.0);
+        let velocity = spin.bounce(Vec2::new(30.0, 40.0), Vec2::Y, 7.5);
+
+        // Backspin on the way down slows the ball's sideways movement
+        assert!(velocity.x < 30.0);
+        assert!((velocity.length() - 50.0).abs() < 1e-3);
+        assert!(spin.0 < 2.0);
+    }
+}
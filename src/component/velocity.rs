@@ -27,3 +27,7 @@ impl From<Vec2> for Velocity {
 /// Represents an entity's friction, their reduction in velocity over time.
 #[derive(Debug, Default, Clone, Copy, Deref, DerefMut, Component)]
 pub struct Friction(pub f32);
+
+/// The fastest an entity can move under its own power.
+#[derive(Debug, Default, Clone, Copy, Deref, DerefMut, Component)]
+pub struct MaxSpeed(pub f32);
@@ -55,8 +55,8 @@ impl Default for Bundle {
                 },
                 ..Default::default()
             },
-            collider: Collider::default(),
-            wall: Wall::default(),
+            collider: Collider,
+            wall: Wall,
         }
     }
 }
@@ -0,0 +1,102 @@
+use std::{fmt, fs, io, path::PathBuf};
+
+use bevy::prelude::warn;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// The directory under the user's config directory that the game's files are
+/// kept in.
+const CONFIG_DIR: &str = "bevy_pong";
+
+/// Something that went wrong reading or writing a config file.
+#[derive(Debug)]
+pub enum Error {
+    /// The file couldn't be read or written.
+    Io(io::Error),
+    /// The file isn't valid RON, or doesn't hold what it should.
+    Parse(ron::error::SpannedError),
+    /// The value couldn't be written out as RON.
+    Serialize(ron::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::Parse(e) => write!(f, "{e}"),
+            Self::Serialize(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Where the config file with the given name lives. Returns `None` on
+/// platforms without a config directory, like the web.
+pub fn path(file_name: &str) -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(CONFIG_DIR).join(file_name))
+}
+
+/// Reads a value from RON text.
+pub fn from_str<T: DeserializeOwned>(text: &str) -> Result<T, Error> {
+    ron::from_str(text).map_err(Error::Parse)
+}
+
+/// Writes a value out as RON text.
+pub fn to_string<T: Serialize>(value: &T) -> Result<String, Error> {
+    ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()).map_err(Error::Serialize)
+}
+
+/// Reads a value from the config file with the given name. Returns `Ok(None)`
+/// if the file hasn't been written yet.
+pub fn load<T: DeserializeOwned>(file_name: &str) -> Result<Option<T>, Error> {
+    let Some(path) = path(file_name) else {
+        return Ok(None);
+    };
+
+    match fs::read_to_string(path) {
+        Ok(text) => from_str(&text).map(Some),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(Error::Io(e)),
+    }
+}
+
+/// Reads a value from the config file with the given name, falling back to
+/// the default if it hasn't been written yet or can't be read.
+pub fn load_or_default<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    load(file_name)
+        .unwrap_or_else(|e| {
+            warn!("Couldn't read {}, using the defaults: {}", file_name, e);
+            None
+        })
+        .unwrap_or_default()
+}
+
+/// Writes a value to the config file with the given name, creating the config
+/// directory if needed. Does nothing on platforms without a config directory.
+pub fn save<T: Serialize>(file_name: &str, value: &T) -> Result<(), Error> {
+    let Some(path) = path(file_name) else {
+        return Ok(());
+    };
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(Error::Io)?;
+    }
+    fs::write(path, to_string(value)?).map_err(Error::Io)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let text = to_string(&(1, "two".to_string())).unwrap();
+        let value: (i32, String) = from_str(&text).unwrap();
+        assert_eq!(value, (1, "two".to_string()));
+
+        assert!(matches!(
+            from_str::<(i32, String)>("(1,"),
+            Err(Error::Parse(_))
+        ));
+    }
+}
@@ -24,8 +24,51 @@ pub const PADDLE_SPEED_MULTIPLIER: f32 = 40.0;
 /// Controls the dimensions of the paddles.
 pub const PADDLE_SCALE: Vec3 = Vec3::new(20.0, 100.0, 1.0);
 
+/// The steepest angle, in radians, that the ball can come off a paddle at. The
+/// ball leaves at this angle when it strikes the very end of a paddle.
+pub const MAX_BOUNCE_ANGLE: f32 = std::f32::consts::FRAC_PI_3;
+/// How much of a paddle's velocity is added to the ball when it is hit.
+pub const PADDLE_VELOCITY_TRANSFER: f32 = 0.25;
+
+/// How much spin a paddle puts on the ball, in radians per second, for each
+/// unit of speed the paddle has along its face.
+pub const SPIN_TRANSFER: f32 = 0.1;
+/// The fastest the ball can spin, in radians per second.
+pub const MAX_SPIN: f32 = 10.0;
+/// How sharply spin curves the ball's path. The ball's direction turns by this
+/// many radians per second for each radian per second of spin.
+pub const MAGNUS_FACTOR: f32 = 0.05;
+/// How quickly the ball loses its spin. Spin decays exponentially at this rate
+/// per second.
+pub const SPIN_DECAY: f32 = 0.2;
+/// How much of the ball's spin a wall turns into sideways speed when the ball
+/// bounces off it.
+pub const WALL_SPIN_GRIP: f32 = 0.3;
+
+/// How far a gamepad stick has to be pushed before it moves the paddle, from
+/// 0.0 to 1.0.
+pub const STICK_DEAD_ZONE: f32 = 0.2;
+/// How far a gamepad stick has to be pushed to move the paddle at full speed,
+/// from 0.0 to 1.0.
+pub const STICK_OUTER_ZONE: f32 = 0.95;
+
+/// How loud the background music is, from 0.0 to 1.0.
+pub const MUSIC_VOLUME: f32 = 1.0;
+/// How loud the sound effects are, from 0.0 to 1.0.
+pub const EFFECTS_VOLUME: f32 = 1.0;
+
 /// The number of points a player must score to win a game.
 pub const WIN_SCORE: u64 = 5;
+/// How far ahead a player has to be to win a game. A margin of 1 makes it
+/// first to `WIN_SCORE`.
+pub const WIN_MARGIN: u64 = 1;
+/// The score at which a game ends regardless of the margin, if any.
+pub const MAX_SCORE: Option<u64> = None;
+/// How many games are in a series.
+pub const GAMES_IN_SERIES: u32 = 1;
+/// How many goals a player can let in before they are knocked out of an
+/// elimination game.
+pub const LIVES: u64 = 5;
 
 /// The width and height of the top wall.
 pub const TOP_WALL_SIZE: Vec2 = Vec2::new(500.0, 25.0);
@@ -39,10 +82,85 @@ pub const BOTTOM_WALL_POSITION: Vec2 = Vec2::new(0.0, -250.0);
 
 /// The default speed of the ball.
 pub const DEFAULT_BALL_SPEED: f32 = 50.0;
+/// The ball's speed is multiplied by this every time a paddle hits it.
+pub const BALL_SPEED_UP_PER_HIT: f32 = 1.05;
+/// The fastest the ball can go.
+pub const MAX_BALL_SPEED: f32 = DEFAULT_BALL_SPEED * 8.0;
+/// How much faster the ball gets every second during wall practice, as a
+/// fraction of its speed.
+pub const PRACTICE_SPEED_UP: f32 = 0.02;
+/// How long the ball waits at the centre before it is served, in seconds.
+pub const SERVE_COUNTDOWN: f32 = 3.0;
+/// The steepest angle, in radians, above or below the horizontal that the ball
+/// can be served at.
+pub const MAX_SERVE_ANGLE: f32 = std::f32::consts::FRAC_PI_6;
+/// The X and Y coordinates of the serve countdown text.
+pub const COUNTDOWN_POSITION: Vec2 = Vec2::new(0.0, 100.0);
 /// The default starting position of the ball.
 pub const BALL_DEFAULT_STARTING_POSITION: Vec2 = Vec2::new(0.0, 0.0);
 /// The dimensions of the ball.
 // TODO: can we make the ball more like a circle for collision purposes?
 pub const BALL_SCALE: Vec3 = Vec3::new(15.0, 15.0, 15.0);
 
+/// The most wall bounces an AI follows when predicting where the ball will go.
+pub const MAX_PREDICTED_BOUNCES: usize = 16;
+/// The most times the ball can bounce within a single fixed step. Keeps a ball
+/// wedged between two colliders from bouncing back and forth forever.
+pub const MAX_BALL_BOUNCES_PER_STEP: usize = 4;
+
 pub const TIME_STEP: f32 = 1.0 / 60.0;
+
+/// How many seconds apart power-ups turn up on the court.
+pub const POWER_UP_INTERVAL: f32 = 8.0;
+/// How many seconds a power-up waits on the court to be collected.
+pub const POWER_UP_LIFETIME: f32 = 6.0;
+/// The most power-ups that can be waiting on the court at once.
+pub const MAX_POWER_UPS_ON_COURT: usize = 2;
+/// The dimensions of a power-up on the court.
+pub const POWER_UP_SCALE: Vec3 = Vec3::new(20.0, 20.0, 1.0);
+/// How many seconds a power-up's effect lasts.
+pub const POWER_UP_DURATION: f32 = 8.0;
+/// How much taller an enlarged paddle is.
+pub const ENLARGE_FACTOR: f32 = 1.5;
+/// How much shorter a shrunk paddle is.
+pub const SHRINK_FACTOR: f32 = 0.6;
+/// How much faster a sped up ball moves.
+pub const SPEED_UP_FACTOR: f32 = 1.5;
+/// How much slower a slowed down ball moves.
+pub const SLOW_DOWN_FACTOR: f32 = 0.6;
+/// The longest a sticky paddle holds on to the ball before letting it go.
+pub const STICKY_HOLD_TIME: f32 = 1.5;
+/// How many balls are served at once in multiball mode.
+pub const MULTIBALL_BALLS: usize = 3;
+/// How far apart balls are lined up for the serve in multiball mode.
+pub const MULTIBALL_SPACING: f32 = 40.0;
+/// The angle, in radians, between a ball and the one split off from it by the
+/// multiball power-up.
+pub const MULTIBALL_SPLIT_ANGLE: f32 = 0.35;
+
+/// The width and height of the Breakout court, inside its walls.
+pub const BREAKOUT_COURT_SIZE: Vec2 = Vec2::new(500.0, 500.0);
+/// How thick the walls around the Breakout court are.
+pub const BREAKOUT_WALL_THICKNESS: f32 = 25.0;
+/// How far above the bottom of the Breakout court the paddle plays.
+pub const BREAKOUT_PADDLE_HEIGHT: f32 = 40.0;
+/// Where the ball is served from in Breakout.
+pub const BREAKOUT_BALL_POSITION: Vec2 = Vec2::new(0.0, -50.0);
+/// The X and Y coordinates of the Breakout serve countdown text.
+pub const BREAKOUT_COUNTDOWN_POSITION: Vec2 = Vec2::new(0.0, -100.0);
+/// The X and Y coordinates of the Breakout score, lives and level text.
+pub const BREAKOUT_STATUS_POSITION: Vec2 = Vec2::new(0.0, TOP_WALL_POSITION.y + 40.0);
+/// How many times the ball can be missed in a Breakout run.
+pub const BREAKOUT_LIVES: u32 = 3;
+/// The width and height of a brick.
+pub const BRICK_SIZE: Vec2 = Vec2::new(45.0, 18.0);
+/// How far apart the centres of neighbouring bricks are.
+pub const BRICK_SPACING: Vec2 = Vec2::new(50.0, 25.0);
+/// The centre of the brick in the top left corner of a level.
+pub const BRICK_GRID_ORIGIN: Vec2 = Vec2::new(-225.0, 200.0);
+/// The most bricks a row of a level can have.
+pub const BRICK_COLUMNS: usize = 10;
+/// The most rows of bricks a level can have.
+pub const BRICK_ROWS: usize = 8;
+/// How many points each hit on a brick is worth.
+pub const BRICK_POINTS: u64 = 10;
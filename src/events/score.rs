@@ -7,11 +7,23 @@ use crate::component::paddle::Side;
 pub struct Event {
     /// The side of the player who scored.
     pub player_side: Side,
+    /// The side whose goal the ball went into.
+    pub conceded: Side,
 }
 
 impl Event {
-    /// Creates a new score event.
+    /// Creates a new score event, with the ball going into the opposite goal.
     pub const fn new(player_side: Side) -> Self {
-        Self { player_side }
+        Self {
+            player_side,
+            conceded: player_side.opposite(),
+        }
+    }
+
+    #[must_use]
+    /// Sets whose goal the ball went into.
+    pub const fn conceded_by(mut self, side: Side) -> Self {
+        self.conceded = side;
+        self
     }
 }
@@ -0,0 +1,193 @@
+use std::fmt;
+
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset},
+    prelude::Vec2,
+    reflect::TypeUuid,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{BRICK_COLUMNS, BRICK_GRID_ORIGIN, BRICK_ROWS, BRICK_SPACING};
+
+/// The file extension level assets are loaded from.
+pub const EXTENSION: &str = "level.ron";
+
+/// The bricks to break in one level of Breakout.
+///
+/// Levels are loaded from `.level.ron` files in the `levels` asset folder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TypeUuid)]
+#[uuid = "c1e7a3b2-58d4-4f0e-a6b9-2d3f8e4c7a15"]
+pub struct Level {
+    /// The name shown while the level is played.
+    pub name: String,
+    /// The rows of bricks, from the top down. Each character is a brick,
+    /// given by how many hits it takes to break, from 1 to 9. A space or a
+    /// `.` leaves a gap.
+    pub rows: Vec<String>,
+}
+
+/// Why a level can't be played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Invalid {
+    /// There are no bricks to break.
+    NoBricks,
+    /// There are more rows than fit on the court.
+    TooManyRows,
+    /// A row has more bricks than fit across the court.
+    TooWide {
+        /// The row, counting from 0 at the top.
+        row: usize,
+    },
+    /// A row has a character that isn't a brick or a gap.
+    Unknown {
+        /// The row, counting from 0 at the top.
+        row: usize,
+        /// The character.
+        found: char,
+    },
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoBricks => write!(f, "there are no bricks"),
+            Self::TooManyRows => write!(f, "there can be at most {BRICK_ROWS} rows"),
+            Self::TooWide { row } => {
+                write!(f, "row {row} has more than {BRICK_COLUMNS} bricks")
+            }
+            Self::Unknown { row, found } => {
+                write!(f, "row {row} has {found:?}, which isn't 1 to 9 or a gap")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Invalid {}
+
+impl Level {
+    /// Where each brick goes, and how many hits it takes to break. Characters
+    /// that aren't bricks are skipped.
+    pub fn bricks(&self) -> impl Iterator<Item = (Vec2, u32)> + '_ {
+        self.rows.iter().enumerate().flat_map(|(row, bricks)| {
+            bricks.chars().enumerate().filter_map(move |(column, c)| {
+                let hits = c.to_digit(10).filter(|&hits| hits > 0)?;
+                let offset = Vec2::new(column as f32, -(row as f32)) * BRICK_SPACING;
+                Some((BRICK_GRID_ORIGIN + offset, hits))
+            })
+        })
+    }
+
+    /// Checks that the level can be played: it needs at least one brick, and
+    /// every row has to fit on the court.
+    pub fn validate(&self) -> Result<(), Invalid> {
+        if self.rows.len() > BRICK_ROWS {
+            return Err(Invalid::TooManyRows);
+        }
+        for (row, bricks) in self.rows.iter().enumerate() {
+            if bricks.chars().count() > BRICK_COLUMNS {
+                return Err(Invalid::TooWide { row });
+            }
+            let unknown = bricks
+                .chars()
+                .find(|&c| !matches!(c, '1'..='9' | ' ' | '.'));
+            if let Some(found) = unknown {
+                return Err(Invalid::Unknown { row, found });
+            }
+        }
+        if self.bricks().next().is_none() {
+            return Err(Invalid::NoBricks);
+        }
+        Ok(())
+    }
+}
+
+impl Default for Level {
+    /// A wall of bricks that break in one hit, used when no level file has
+    /// been loaded.
+    fn default() -> Self {
+        Self {
+            name: "Practice Wall".to_string(),
+            rows: vec!["1111111111".to_string(); 3],
+        }
+    }
+}
+
+/// Loads levels from `.level.ron` files, refusing any that can't be played.
+#[derive(Debug, Default)]
+pub struct Loader;
+
+impl AssetLoader for Loader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let level: Level = ron::de::from_bytes(bytes)?;
+            level.validate()?;
+            load_context.set_default_asset(LoadedAsset::new(level));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[EXTENSION]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_shipped_levels_are_playable() {
+        for file in crate::resources::Levels::FILES {
+            let path = format!("{}/assets/{file}", env!("CARGO_MANIFEST_DIR"));
+            let bytes = std::fs::read(&path).unwrap();
+            let level: Level = ron::de::from_bytes(&bytes).unwrap();
+            assert_eq!(level.validate(), Ok(()), "{path}");
+        }
+        assert_eq!(Level::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_bricks_are_laid_out_in_a_grid() {
+        let level = Level {
+            name: "Test".to_string(),
+            rows: vec!["1.3".to_string(), " 9".to_string()],
+        };
+        let bricks: Vec<_> = level.bricks().collect();
+        assert_eq!(
+            bricks,
+            vec![
+                (BRICK_GRID_ORIGIN, 1),
+                (BRICK_GRID_ORIGIN + Vec2::new(2.0 * BRICK_SPACING.x, 0.0), 3),
+                (
+                    BRICK_GRID_ORIGIN + Vec2::new(BRICK_SPACING.x, -BRICK_SPACING.y),
+                    9
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_level_needs_bricks_that_fit() {
+        let mut level = Level {
+            name: "Test".to_string(),
+            rows: vec!["....".to_string()],
+        };
+        assert_eq!(level.validate(), Err(Invalid::NoBricks));
+
+        level.rows.push("12345678901".to_string());
+        assert_eq!(level.validate(), Err(Invalid::TooWide { row: 1 }));
+
+        level.rows[1] = "12x".to_string();
+        assert_eq!(
+            level.validate(),
+            Err(Invalid::Unknown { row: 1, found: 'x' })
+        );
+
+        level.rows = vec!["1".to_string(); BRICK_ROWS + 1];
+        assert_eq!(level.validate(), Err(Invalid::TooManyRows));
+    }
+}
@@ -13,21 +13,30 @@
 //! A simple Pong clone built with Bevy.
 //!
 //! Features:
-//! - 2 player local multiplayer (WASD and arrow keys)
+//! - 2 player local multiplayer (WASD and arrow keys, gamepads, mouse or
+//!   touch)
 //! - Sound effects
+//! - Arenas loaded from asset files, picked from the main menu
+//! - Power-ups with timed effects, shown under each player's score
+//! - Multiball mode, toggled from the main menu
+//! - A four-player arena, played for points or by elimination
+//! - 2v2 doubles, with a human or computer partner
+//! - Wall practice, with a personal best
+//! - Breakout, with levels loaded from asset files and a high score
 //! - Dynamic screen shake based on relative speed of colliding objects
 use bevy::{
+    input::InputSystem,
     prelude::{
-        App, ClearColor, Color, FixedTime, IntoSystemAppConfig, IntoSystemConfig,
-        IntoSystemConfigs, KeyCode, Msaa, OnEnter, OnExit, OnUpdate, Plugin,
+        in_state, not, AddAsset, App, ClearColor, Color, CoreSchedule, CoreSet, FixedTime,
+        IntoSystemAppConfig, IntoSystemAppConfigs, IntoSystemConfig, IntoSystemConfigs,
+        IntoSystemSetConfig, KeyCode, Msaa, OnEnter, OnExit, OnUpdate, Plugin,
     },
     time::{Timer, TimerMode},
 };
 use bevy_prototype_lyon::prelude::ShapePlugin;
-use component::collider;
+use component::{collider, controls};
 use constants::TIME_STEP;
 use events::score;
-use plugins::window_scaling_2d::constants::ASPECT_RATIO_4_3;
 use states::AppState;
 use systems::LogSamplingTimer;
 
@@ -35,14 +44,22 @@ use systems::LogSamplingTimer;
 mod systems;
 mod tests;
 
+/// Arena layouts, loaded from asset files.
+pub mod arena;
 /// Components used to compose game objects.
 pub mod component;
+/// Reading and writing the player's config files.
+pub mod config;
 /// Constants used throughout the game.
 pub mod constants;
 /// Events that can be emitted by the game.
 pub mod events;
+/// Breakout levels, loaded from asset files.
+pub mod level;
 /// Reusable plugins.
 pub mod plugins;
+/// Resources shared between systems.
+pub mod resources;
 /// Game states.
 pub mod states;
 
@@ -67,41 +84,195 @@ impl Plugin for PongPlugin {
                 1.0,
                 TimerMode::Repeating,
             )))
-            .add_plugin(
-                plugins::window_scaling_2d::Plugin::default()
-                    .with_locked_aspect_ratio(ASPECT_RATIO_4_3),
-            )
+            // The aspect ratio is locked by the settings
+            .add_plugin(plugins::window_scaling_2d::Plugin::default())
             .insert_resource(FixedTime::new_from_secs(TIME_STEP))
+            .init_resource::<resources::Deflection>()
+            .init_resource::<resources::RallySpeed>()
+            .init_resource::<resources::Serve>()
+            .init_resource::<resources::MatchRules>()
+            .init_resource::<resources::Series>()
+            .init_resource::<resources::MatchStats>()
+            .init_resource::<resources::Opponent>()
+            .init_resource::<resources::PowerUps>()
+            .init_resource::<resources::Multiball>()
+            .init_resource::<resources::Teammate>()
+            .add_asset::<arena::Arena>()
+            .init_asset_loader::<arena::Loader>()
+            .init_resource::<resources::Arenas>()
+            .add_asset::<level::Level>()
+            .init_asset_loader::<level::Loader>()
+            .init_resource::<resources::Levels>()
+            .insert_resource(resources::Settings::load())
+            .add_system(systems::apply_settings.in_base_set(CoreSet::PreUpdate))
+            .add_system(systems::save_settings)
+            .insert_resource(resources::Bindings::load())
+            .insert_resource(resources::Practice::load())
+            .insert_resource(resources::Breakout::load())
+            .init_resource::<resources::GamepadAssignment>()
+            .init_resource::<resources::StickDeadZone>()
             // Game resources and state
             .add_state::<AppState>()
             .add_event::<score::Event>()
             .add_event::<KeyCode>()
             .add_event::<collider::Event>()
+            .add_event::<controls::ActionEvent>()
+            // Controller scheduling
+            // Every controller is read into its paddle's actions as soon as
+            // the input devices have been updated for the frame
+            .add_systems(
+                (
+                    systems::assign_gamepads,
+                    systems::use_assigned_gamepads,
+                    systems::use_pointer_controllers,
+                    systems::keyboard_actions,
+                    systems::gamepad_actions,
+                    systems::mouse_actions,
+                    systems::touch_actions,
+                    systems::remote_actions,
+                )
+                    .chain()
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(InputSystem),
+            )
+            .add_system(
+                systems::latch_serves
+                    .run_if(in_state(AppState::InGame))
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(systems::remote_actions),
+            )
+            // End controller scheduling
             // Menu scheduling
             .add_system(systems::setup_main_menu.in_schedule(OnEnter(AppState::MainMenu)))
             .add_system(systems::read_keypresses.in_set(OnUpdate(AppState::MainMenu)))
+            .add_system(systems::update_opponent_text.in_set(OnUpdate(AppState::MainMenu)))
+            .add_system(systems::update_arena_text.in_set(OnUpdate(AppState::MainMenu)))
+            .add_system(systems::update_multiball_text.in_set(OnUpdate(AppState::MainMenu)))
+            .add_system(systems::update_teammate_text.in_set(OnUpdate(AppState::MainMenu)))
             .add_system(systems::teardown_main_menu.in_schedule(OnExit(AppState::MainMenu)))
+            // Controls menu scheduling
+            .add_system(systems::setup_controls_menu.in_schedule(OnEnter(AppState::Controls)))
+            .add_systems(
+                (
+                    systems::read_controls_menu_keypresses,
+                    systems::update_controls_menu_text,
+                )
+                    .chain()
+                    .in_set(OnUpdate(AppState::Controls)),
+            )
+            .add_systems(
+                (systems::save_bindings, systems::teardown_controls_menu)
+                    .in_schedule(OnExit(AppState::Controls)),
+            )
             // End menu scheduling
             // Game scheduling
-            .add_system(systems::initialize_match.in_schedule(OnEnter(AppState::InGame)))
-            .add_system(systems::start_background_music.in_schedule(OnEnter(AppState::InGame)))
+            // Resuming from the pause menu re-enters the game state with the
+            // match still in place, so it is only set up when there isn't one
+            .add_system(
+                systems::initialize_match
+                    .run_if(systems::no_active_match)
+                    .run_if(systems::playing_pong)
+                    .in_schedule(OnEnter(AppState::InGame)),
+            )
+            .add_system(
+                systems::initialize_breakout
+                    .run_if(systems::no_active_match)
+                    .run_if(systems::playing_breakout)
+                    .in_schedule(OnEnter(AppState::InGame)),
+            )
+            .add_system(
+                systems::start_background_music
+                    .run_if(systems::no_active_match)
+                    .in_schedule(OnEnter(AppState::InGame)),
+            )
+            .add_system(systems::collision_sound.in_set(OnUpdate(AppState::InGame)))
+            .add_system(systems::show_countdown.in_set(OnUpdate(AppState::InGame)))
+            .add_system(systems::show_effect_icons.in_set(OnUpdate(AppState::InGame)))
+            .add_system(systems::show_breakout_status.in_set(OnUpdate(AppState::InGame)))
+            // The simulation runs on the fixed timestep so that it behaves the
+            // same regardless of the frame rate. Bevy accumulates frame time
+            // and runs as many fixed steps as fit into it each frame.
+            .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+                schedule
+                    .configure_set(OnUpdate(AppState::InGame).run_if(in_state(AppState::InGame)));
+            })
+            .add_systems(
+                systems::simulation_systems()
+                    .in_set(OnUpdate(AppState::InGame))
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_systems(
+                systems::arena_systems()
+                    .in_set(OnUpdate(AppState::InGame))
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_systems(
+                systems::practice_systems()
+                    .distributive_run_if(systems::playing_pong)
+                    .in_set(OnUpdate(AppState::InGame))
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                systems::end_practice_run
+                    .after(systems::handle_score_event)
+                    .before(systems::detect_win_condition)
+                    .run_if(systems::playing_pong)
+                    .in_set(OnUpdate(AppState::InGame))
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            // Breakout plays out on the same court systems as Pong, with
+            // bricks to break and lives to lose on top
+            .add_system(
+                systems::break_bricks
+                    .after(systems::collide_ball)
+                    .before(systems::detect_score)
+                    .run_if(systems::playing_breakout)
+                    .in_set(OnUpdate(AppState::InGame))
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                systems::lose_life
+                    .after(systems::handle_score_event)
+                    .before(systems::detect_win_condition)
+                    .run_if(systems::playing_breakout)
+                    .in_set(OnUpdate(AppState::InGame))
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            // The state has already changed by the time the exit schedule runs,
+            // so this is how we can tell that the match is only being paused
+            .add_system(
+                systems::clear_active_match
+                    .run_if(not(in_state(AppState::Paused)))
+                    .in_schedule(OnExit(AppState::InGame)),
+            )
+            .add_system(
+                systems::stop_background_music
+                    .run_if(not(in_state(AppState::Paused)))
+                    .in_schedule(OnExit(AppState::InGame)),
+            )
+            // End game scheduling
+            // Pause scheduling
+            .add_system(systems::toggle_pause)
+            .add_system(systems::pause_on_focus_loss.in_set(OnUpdate(AppState::InGame)))
+            .configure_set(plugins::shake::ShakeSet.run_if(not(in_state(AppState::Paused))))
+            .add_system(systems::setup_pause_menu.in_schedule(OnEnter(AppState::Paused)))
+            .add_system(systems::pause_background_music.in_schedule(OnEnter(AppState::Paused)))
+            .add_system(systems::read_pause_menu_keypresses.in_set(OnUpdate(AppState::Paused)))
+            .add_system(systems::resume_background_music.in_schedule(OnExit(AppState::Paused)))
+            .add_system(systems::teardown_pause_menu.in_schedule(OnExit(AppState::Paused)))
+            // End pause scheduling
+            // Game over scheduling
             .add_systems(
                 (
-                    systems::collision_sound,
-                    systems::paddle_input,
-                    systems::collide_ball,
-                    systems::move_ball.after(systems::collide_ball),
-                    systems::move_paddles,
-                    systems::apply_friction,
-                    systems::detect_score,
-                    systems::handle_score_event.before(systems::detect_win_condition),
-                    systems::detect_win_condition,
+                    systems::setup_game_over,
+                    systems::save_personal_best,
+                    systems::save_high_score,
                 )
-                    .in_set(OnUpdate(AppState::InGame)),
+                    .in_schedule(OnEnter(AppState::GameOver)),
             )
-            .add_system(systems::clear_active_match.in_schedule(OnExit(AppState::InGame)))
-            .add_system(systems::stop_background_music.in_schedule(OnExit(AppState::InGame)))
-            // End game scheduling
+            .add_system(systems::read_game_over_keypresses.in_set(OnUpdate(AppState::GameOver)))
+            .add_system(systems::teardown_game_over.in_schedule(OnExit(AppState::GameOver)))
+            // End game over scheduling
             .register_type::<component::paddle::Player>()
             .register_type::<component::bounding_box::BoundingBox>();
         // .add_system(systems::log_game_state);
@@ -13,7 +13,7 @@ fn main() {
                 ),
         )
         .add_plugin(LogDiagnosticsPlugin::default())
-        .add_plugin(FrameTimeDiagnosticsPlugin::default())
+        .add_plugin(FrameTimeDiagnosticsPlugin)
         // .add_plugin(WorldInspectorPlugin::default())
         .add_plugin(bevy_pong::PongPlugin)
         .run();
@@ -6,7 +6,10 @@ use std::{ops::Mul, time::Duration};
 
 use bevy::{
     ecs::system::Res,
-    prelude::{App, Commands, Entity, EventReader, Plugin as BevyPlugin, Query, Transform},
+    prelude::{
+        App, Commands, Entity, EventReader, IntoSystemConfigs, Plugin as BevyPlugin, Query,
+        SystemSet, Transform,
+    },
     time::Time,
 };
 
@@ -27,10 +30,15 @@ pub struct Plugin;
 impl BevyPlugin for Plugin {
     fn build(&self, app: &mut App) {
         app.add_event::<Event>()
-            .add_systems((handle_shake_events, process_shakes));
+            .add_systems((handle_shake_events, process_shakes).in_set(ShakeSet));
     }
 }
 
+/// The systems that shake entities. Add a run condition to this set to freeze
+/// shakes in place, for example while the game is paused.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShakeSet;
+
 #[derive(Debug, Clone, Copy)]
 /// Send this event to start a screen shake.
 pub struct Event {
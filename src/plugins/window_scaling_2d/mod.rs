@@ -1,11 +1,13 @@
-//! This plugin scales a 2D camera projection to window height. It can also lock
-//! the aspect ratio of the window to a specific ratio, but does not do so by
-//! default. It is intended to be a drop-in solution for 2D games that want to
-//! offer multiple resolutions while keeping their logical game coordinates
-//! consistent.
+//! This plugin scales a 2D camera projection to window height.
+//!
+//! It can also lock the aspect ratio of the window to a specific ratio, but
+//! does not do so by default. It is intended to be a drop-in solution for 2D
+//! games that want to offer multiple resolutions while keeping their logical
+//! game coordinates consistent.
 
 use self::resources::AspectRatio;
 use bevy::{
+    prelude::Vec2,
     prelude::{
         debug, App, Camera2d, DetectChanges, OrthographicProjection, Plugin as BevyPlugin, Query,
         Res, With,
@@ -17,6 +19,7 @@ pub mod constants;
 pub mod resources;
 
 /// Add this plugin to your app to enable scaling a 2D camera to window height.
+///
 /// Resizing the window will scale the camera's orthographic projection to fit
 /// the window vertically without affecting your logical game coordinates.
 ///
@@ -80,3 +83,23 @@ pub fn update_projection_scale(
         window.resolution.set(width, height);
     }
 }
+
+/// Converts a position in the window into logical game coordinates, so that
+/// things like the mouse cursor line up with the game no matter how big the
+/// window is.
+///
+/// `position` is in logical window pixels with the origin at the bottom left,
+/// like `Window::cursor_position`. `scale` is the scale of the camera's
+/// projection, which this plugin keeps up to date. The camera is assumed to be
+/// looking at the origin.
+pub fn window_to_logical(window: &Window, position: Vec2, scale: f32) -> Vec2 {
+    let size = Vec2::new(window.width(), window.height());
+    (position - size / 2.0) * scale
+}
+
+/// Converts the position of a touch into the same window coordinates as
+/// `Window::cursor_position`. Touches are reported in logical pixels like the
+/// cursor, but with the origin at the top left.
+pub fn touch_to_window(window: &Window, position: Vec2) -> Vec2 {
+    Vec2::new(position.x, window.height() - position.y)
+}
@@ -0,0 +1,89 @@
+use bevy::{
+    asset::{AssetServer, Assets, Handle},
+    prelude::{FromWorld, Resource, World},
+};
+
+use crate::arena::Arena;
+
+/// The arenas that can be picked from the main menu, and which one is picked.
+#[derive(Resource, Debug, Clone)]
+pub struct Arenas {
+    /// The arenas being loaded, in the order the menu shows them.
+    pub handles: Vec<Handle<Arena>>,
+    /// The position of the picked arena in `handles`.
+    pub selected: usize,
+}
+
+impl Arenas {
+    /// The arena files shipped with the game, in the order the menu shows
+    /// them.
+    pub const FILES: [&'static str; 6] = [
+        "arenas/classic.arena.ron",
+        "arenas/wide.arena.ron",
+        "arenas/pillarbox.arena.ron",
+        "arenas/bumpers.arena.ron",
+        "arenas/four.arena.ron",
+        "arenas/doubles.arena.ron",
+    ];
+
+    /// Picks the next arena in the menu, wrapping around.
+    pub const fn next(&mut self) {
+        if !self.handles.is_empty() {
+            self.selected = (self.selected + 1) % self.handles.len();
+        }
+    }
+
+    /// Picks the previous arena in the menu, wrapping around.
+    pub const fn previous(&mut self) {
+        if !self.handles.is_empty() {
+            self.selected = (self.selected + self.handles.len() - 1) % self.handles.len();
+        }
+    }
+
+    /// The picked arena, if it has finished loading.
+    pub fn selected<'a>(&self, assets: &'a Assets<Arena>) -> Option<&'a Arena> {
+        self.handles
+            .get(self.selected)
+            .and_then(|handle| assets.get(handle))
+    }
+}
+
+impl FromWorld for Arenas {
+    /// Starts loading every arena shipped with the game.
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        Self {
+            handles: Self::FILES
+                .iter()
+                .map(|file| asset_server.load(*file))
+                .collect(),
+            selected: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cycle_arenas() {
+        let mut arenas = Arenas {
+            handles: vec![Handle::default(), Handle::default()],
+            selected: 0,
+        };
+        arenas.next();
+        assert_eq!(arenas.selected, 1);
+        arenas.next();
+        assert_eq!(arenas.selected, 0);
+        arenas.previous();
+        assert_eq!(arenas.selected, 1);
+
+        let mut empty = Arenas {
+            handles: Vec::new(),
+            selected: 0,
+        };
+        empty.previous();
+        assert_eq!(empty.selected, 0);
+    }
+}
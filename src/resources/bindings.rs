@@ -0,0 +1,166 @@
+use bevy::prelude::{warn, KeyCode, Resource};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::{
+        controls::{Action, Keyboard},
+        paddle::Side,
+    },
+    config,
+};
+
+/// The name of the config file the bindings are saved in.
+const FILE_NAME: &str = "controls.ron";
+
+/// The keys each player uses. These are loaded from the player's config file
+/// at startup, and saved again whenever they are changed in the controls menu.
+///
+/// Files saved before there were four players get the built-in keys for the
+/// top and bottom.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bindings {
+    /// The keys the left player uses.
+    pub left: Keyboard,
+    /// The keys the right player uses.
+    pub right: Keyboard,
+    /// The keys the top player uses.
+    pub top: Keyboard,
+    /// The keys the bottom player uses.
+    pub bottom: Keyboard,
+}
+
+/// A key that is already bound to something else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conflict {
+    /// The side that is already using the key.
+    pub side: Side,
+    /// What the key already does for that side.
+    pub action: Action,
+}
+
+impl Bindings {
+    /// Loads the bindings from the player's config file, falling back to the
+    /// built-in layouts if there aren't any saved.
+    pub fn load() -> Self {
+        config::load_or_default(FILE_NAME)
+    }
+
+    /// Saves the bindings to the player's config file.
+    pub fn save(&self) {
+        if let Err(e) = config::save(FILE_NAME, self) {
+            warn!("Couldn't save the controls: {}", e);
+        }
+    }
+
+    /// The keys `side` uses.
+    pub const fn get(&self, side: Side) -> Keyboard {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+            Side::Top => self.top,
+            Side::Bottom => self.bottom,
+        }
+    }
+
+    /// Returns what `key` is already bound to, if binding it to `action` for
+    /// `side` would clash with it. Players may share a pause key, since it does
+    /// the same thing for any of them.
+    pub fn conflict(&self, side: Side, action: Action, key: KeyCode) -> Option<Conflict> {
+        Side::ALL
+            .into_iter()
+            .flat_map(|side| Action::ALL.map(|action| Conflict { side, action }))
+            .filter(|other| (other.side, other.action) != (side, action))
+            .filter(|other| !(other.action == Action::Pause && action == Action::Pause))
+            .find(|other| self.get(other.side).key(other.action) == key)
+    }
+
+    /// Binds `key` to `action` for `side`, unless it is already bound to
+    /// something else.
+    pub fn bind(&mut self, side: Side, action: Action, key: KeyCode) -> Result<(), Conflict> {
+        if let Some(conflict) = self.conflict(side, action, key) {
+            return Err(conflict);
+        }
+
+        match side {
+            Side::Left => self.left.set_key(action, key),
+            Side::Right => self.right.set_key(action, key),
+            Side::Top => self.top.set_key(action, key),
+            Side::Bottom => self.bottom.set_key(action, key),
+        }
+        Ok(())
+    }
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            left: Keyboard::for_side(Side::Left),
+            right: Keyboard::for_side(Side::Right),
+            top: Keyboard::for_side(Side::Top),
+            bottom: Keyboard::for_side(Side::Bottom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rebinding() {
+        let mut bindings = Bindings::default();
+
+        assert_eq!(
+            bindings.bind(Side::Left, Action::MoveUp, KeyCode::T),
+            Ok(())
+        );
+        assert_eq!(bindings.left.key(Action::MoveUp), KeyCode::T);
+
+        // Taken by the other player
+        assert_eq!(
+            bindings.bind(Side::Left, Action::Serve, KeyCode::Up),
+            Err(Conflict {
+                side: Side::Right,
+                action: Action::MoveUp,
+            })
+        );
+        // Taken by another of the same player's actions
+        assert_eq!(
+            bindings.bind(Side::Left, Action::MoveDown, KeyCode::T),
+            Err(Conflict {
+                side: Side::Left,
+                action: Action::MoveUp,
+            })
+        );
+        // Rebinding an action to the key it already has is fine
+        assert_eq!(
+            bindings.bind(Side::Left, Action::MoveUp, KeyCode::T),
+            Ok(())
+        );
+        // Both players can pause with the same key
+        assert_eq!(bindings.bind(Side::Left, Action::Pause, KeyCode::P), Ok(()));
+        assert_eq!(
+            bindings.bind(Side::Right, Action::Pause, KeyCode::P),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_bindings_survive_a_round_trip() {
+        let mut bindings = Bindings::default();
+        bindings
+            .bind(Side::Right, Action::Serve, KeyCode::Return)
+            .unwrap();
+
+        let text = config::to_string(&bindings).unwrap();
+        assert_eq!(config::from_str::<Bindings>(&text).unwrap(), bindings);
+
+        // Two-player files still load
+        let two_players = config::from_str::<Bindings>(
+            "(left: (up: W, down: S, left: A, right: D, serve: LShift, pause: Escape))",
+        )
+        .unwrap();
+        assert_eq!(two_players, Bindings::default());
+    }
+}
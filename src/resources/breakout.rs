@@ -0,0 +1,133 @@
+use bevy::prelude::{warn, Resource};
+use serde::{Deserialize, Serialize};
+
+use crate::{config, constants::BREAKOUT_LIVES};
+
+/// The name of the file the high score is saved in.
+const FILE_NAME: &str = "breakout.ron";
+
+/// How a game of Breakout is going, and the player's high score.
+///
+/// Whether Breakout is being played and the level, lives and score of the
+/// current run are kept for the session. The highest score the player has
+/// ever finished a run with is saved in their config file.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Breakout {
+    /// The highest score the player has finished a run with.
+    pub best: u64,
+    /// Whether Breakout is being played, rather than Pong.
+    #[serde(skip)]
+    pub playing: bool,
+    /// The position in `Levels` of the level being played.
+    #[serde(skip)]
+    pub level: usize,
+    /// How many more times the ball can be missed this run.
+    #[serde(skip)]
+    pub lives: u32,
+    /// The points scored so far this run.
+    #[serde(skip)]
+    pub score: u64,
+    /// Whether every level was cleared this run.
+    #[serde(skip)]
+    pub cleared: bool,
+    /// Whether the last run set a new high score.
+    #[serde(skip)]
+    pub new_best: bool,
+}
+
+impl Breakout {
+    /// Loads the high score from the player's config file, starting from
+    /// scratch if there isn't one.
+    pub fn load() -> Self {
+        config::load_or_default(FILE_NAME)
+    }
+
+    /// Saves the high score to the player's config file.
+    pub fn save(&self) {
+        if let Err(e) = config::save(FILE_NAME, self) {
+            warn!("Couldn't save the high score: {}", e);
+        }
+    }
+
+    /// Starts a new run from the first level.
+    pub const fn start(&mut self) {
+        self.playing = true;
+        self.level = 0;
+        self.lives = BREAKOUT_LIVES;
+        self.score = 0;
+        self.cleared = false;
+        self.new_best = false;
+    }
+
+    /// Whether the run is over, because the player ran out of lives or
+    /// cleared every level.
+    pub const fn is_over(&self) -> bool {
+        self.lives == 0 || self.cleared
+    }
+
+    /// Takes a life away after the ball is missed, ending the run if that
+    /// was the last one.
+    pub fn lose_life(&mut self) {
+        self.lives = self.lives.saturating_sub(1);
+        if self.lives == 0 {
+            self.finish();
+        }
+    }
+
+    /// Moves on to the next of `levels` levels, ending the run if that was
+    /// the last one.
+    pub fn clear_level(&mut self, levels: usize) {
+        self.level += 1;
+        if self.level >= levels {
+            self.cleared = true;
+            self.finish();
+        }
+    }
+
+    /// Keeps the run's score as the high score if it beats it.
+    fn finish(&mut self) {
+        self.new_best = self.score > self.best;
+        self.best = self.best.max(self.score);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_breakout_run() {
+        let mut breakout = Breakout::default();
+        assert!(breakout.is_over());
+
+        breakout.start();
+        breakout.score = 120;
+        breakout.clear_level(2);
+        assert_eq!(breakout.level, 1);
+        for _ in 0..BREAKOUT_LIVES {
+            assert!(!breakout.is_over());
+            breakout.lose_life();
+        }
+        assert!(breakout.is_over());
+        assert!(breakout.new_best);
+        assert_eq!(breakout.best, 120);
+
+        // Clearing every level ends the run too
+        breakout.start();
+        breakout.score = 80;
+        breakout.clear_level(1);
+        assert!(breakout.cleared && breakout.is_over());
+        assert!(!breakout.new_best);
+
+        // Only the high score is saved
+        let text = config::to_string(&breakout).unwrap();
+        assert_eq!(
+            config::from_str::<Breakout>(&text).unwrap(),
+            Breakout {
+                best: 120,
+                ..Default::default()
+            }
+        );
+    }
+}
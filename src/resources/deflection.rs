@@ -0,0 +1,176 @@
+use bevy::prelude::{Resource, Transform, Vec2};
+
+use crate::constants::{MAX_BOUNCE_ANGLE, PADDLE_VELOCITY_TRANSFER};
+
+/// Controls how the ball comes off a paddle.
+///
+/// Like in the original Pong, where the ball strikes the paddle decides which
+/// way it goes: the middle sends it straight back, and the ends send it off at
+/// a steep angle.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct Deflection {
+    /// The angle, in radians, between the paddle's normal and the ball's
+    /// outgoing direction when the ball strikes the very end of the paddle.
+    pub max_bounce_angle: f32,
+    /// How much of the paddle's velocity is added to the ball when it is hit.
+    pub paddle_velocity_transfer: f32,
+}
+
+impl Deflection {
+    #[must_use]
+    /// Sets the maximum bounce angle, in radians.
+    pub const fn with_max_bounce_angle(mut self, max_bounce_angle: f32) -> Self {
+        self.max_bounce_angle = max_bounce_angle;
+        self
+    }
+
+    #[must_use]
+    /// Sets how much of the paddle's velocity is added to the ball.
+    pub const fn with_paddle_velocity_transfer(mut self, paddle_velocity_transfer: f32) -> Self {
+        self.paddle_velocity_transfer = paddle_velocity_transfer;
+        self
+    }
+
+    /// Works out the ball's velocity after bouncing off a paddle. `normal` is
+    /// the normal of the paddle surface that was hit. The ball keeps its speed,
+    /// plus whatever it picks up from the paddle's velocity.
+    ///
+    /// Returns `None` if the ball hit one of the ends of the paddle rather than
+    /// its face, in which case it should just be reflected.
+    pub fn deflect(
+        &self,
+        ball_pos: Vec2,
+        ball_vel: Vec2,
+        paddle_tf: &Transform,
+        paddle_vel: Vec2,
+        normal: Vec2,
+    ) -> Option<Vec2> {
+        let paddle_size = paddle_tf.scale.truncate();
+        // The face of the paddle runs along its longest side
+        let along_face = if paddle_size.y >= paddle_size.x {
+            Vec2::Y
+        } else {
+            Vec2::X
+        };
+        if normal.dot(along_face) != 0.0 {
+            return None;
+        }
+
+        // -1.0 at one end of the paddle, 1.0 at the other
+        let half_length = paddle_size.dot(along_face) / 2.0;
+        let offset = ((ball_pos - paddle_tf.translation.truncate()).dot(along_face) / half_length)
+            .clamp(-1.0, 1.0);
+
+        let angle = offset * self.max_bounce_angle;
+        let direction = normal * angle.cos() + along_face * angle.sin();
+
+        Some(direction * ball_vel.length() + paddle_vel * self.paddle_velocity_transfer)
+    }
+}
+
+impl Default for Deflection {
+    fn default() -> Self {
+        Self {
+            max_bounce_angle: MAX_BOUNCE_ANGLE,
+            paddle_velocity_transfer: PADDLE_VELOCITY_TRANSFER,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::FRAC_PI_4;
+
+    use super::*;
+
+    fn paddle() -> Transform {
+        Transform::from_xyz(100.0, 0.0, 0.0).with_scale((20.0, 100.0, 1.0).into())
+    }
+
+    #[test]
+    fn test_deflect_by_hit_position() {
+        let deflection = Deflection::default()
+            .with_max_bounce_angle(FRAC_PI_4)
+            .with_paddle_velocity_transfer(0.0);
+        let ball_vel = Vec2::new(50.0, -20.0);
+
+        // The middle sends the ball straight back, at the same speed
+        let vel = deflection
+            .deflect(
+                Vec2::new(90.0, 0.0),
+                ball_vel,
+                &paddle(),
+                Vec2::ZERO,
+                Vec2::NEG_X,
+            )
+            .unwrap();
+        assert_eq!(vel, Vec2::new(-ball_vel.length(), 0.0));
+
+        // The top end sends it up at the maximum angle
+        let vel = deflection
+            .deflect(
+                Vec2::new(90.0, 50.0),
+                ball_vel,
+                &paddle(),
+                Vec2::ZERO,
+                Vec2::NEG_X,
+            )
+            .unwrap();
+        assert!((vel.angle_between(Vec2::NEG_X).abs() - FRAC_PI_4).abs() < 1e-5);
+        assert!(vel.y > 0.0);
+        assert!((vel.length() - ball_vel.length()).abs() < 1e-3);
+
+        // Past the bottom end is the same as the bottom end
+        let past_end = deflection
+            .deflect(
+                Vec2::new(90.0, -60.0),
+                ball_vel,
+                &paddle(),
+                Vec2::ZERO,
+                Vec2::NEG_X,
+            )
+            .unwrap();
+        let at_end = deflection
+            .deflect(
+                Vec2::new(90.0, -50.0),
+                ball_vel,
+                &paddle(),
+                Vec2::ZERO,
+                Vec2::NEG_X,
+            )
+            .unwrap();
+        assert_eq!(past_end, at_end);
+        assert!(at_end.y < 0.0);
+    }
+
+    #[test]
+    fn test_deflect_adds_paddle_velocity() {
+        let deflection = Deflection::default().with_paddle_velocity_transfer(0.5);
+
+        let vel = deflection
+            .deflect(
+                Vec2::new(110.0, 0.0),
+                Vec2::new(-50.0, 0.0),
+                &paddle(),
+                Vec2::new(0.0, 40.0),
+                Vec2::X,
+            )
+            .unwrap();
+        assert_eq!(vel, Vec2::new(50.0, 20.0));
+    }
+
+    #[test]
+    fn test_hitting_the_end_of_a_paddle_is_not_deflected() {
+        let deflection = Deflection::default();
+
+        assert!(deflection
+            .deflect(
+                Vec2::new(100.0, 60.0),
+                Vec2::new(0.0, -50.0),
+                &paddle(),
+                Vec2::ZERO,
+                Vec2::Y,
+            )
+            .is_none());
+    }
+}
@@ -0,0 +1,157 @@
+use bevy::prelude::{Gamepad, Resource, Vec2};
+
+use crate::{
+    component::paddle::Side,
+    constants::{STICK_DEAD_ZONE, STICK_OUTER_ZONE},
+};
+
+/// Which gamepad each side is played with. Gamepads are handed out to the
+/// sides in order as they are connected, and a side without one falls back to
+/// the keyboard.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GamepadAssignment {
+    /// The gamepad playing the left side.
+    pub left: Option<Gamepad>,
+    /// The gamepad playing the right side.
+    pub right: Option<Gamepad>,
+    /// The gamepad playing the top side.
+    pub top: Option<Gamepad>,
+    /// The gamepad playing the bottom side.
+    pub bottom: Option<Gamepad>,
+}
+
+impl GamepadAssignment {
+    /// The gamepad playing `side`, if any.
+    pub const fn get(&self, side: Side) -> Option<Gamepad> {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+            Side::Top => self.top,
+            Side::Bottom => self.bottom,
+        }
+    }
+
+    /// Hands a newly connected gamepad to the first side without one. Returns
+    /// the side it was given to, or `None` if every side already has one.
+    pub fn connect(&mut self, gamepad: Gamepad) -> Option<Side> {
+        if let Some(side) = self.side_of(gamepad) {
+            return Some(side);
+        }
+
+        let side = Side::ALL
+            .into_iter()
+            .find(|&side| self.get(side).is_none())?;
+        *self.slot(side) = Some(gamepad);
+        Some(side)
+    }
+
+    /// Takes a disconnected gamepad away from its side. Returns the side that
+    /// lost it, if it had been handed out.
+    pub fn disconnect(&mut self, gamepad: Gamepad) -> Option<Side> {
+        let side = self.side_of(gamepad)?;
+        *self.slot(side) = None;
+        Some(side)
+    }
+
+    /// The side `gamepad` is playing, if any.
+    pub fn side_of(&self, gamepad: Gamepad) -> Option<Side> {
+        Side::ALL
+            .into_iter()
+            .find(|&side| self.get(side) == Some(gamepad))
+    }
+
+    const fn slot(&mut self, side: Side) -> &mut Option<Gamepad> {
+        match side {
+            Side::Left => &mut self.left,
+            Side::Right => &mut self.right,
+            Side::Top => &mut self.top,
+            Side::Bottom => &mut self.bottom,
+        }
+    }
+}
+
+/// How gamepad sticks are read. Small pushes inside the dead zone are ignored
+/// so that a worn stick doesn't drift the paddle, and anything past the outer
+/// zone counts as a full push.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct StickDeadZone {
+    /// How far the stick has to be pushed before it counts, from 0.0 to 1.0.
+    pub inner: f32,
+    /// How far the stick has to be pushed to count as a full push, from 0.0
+    /// to 1.0.
+    pub outer: f32,
+}
+
+impl StickDeadZone {
+    #[must_use]
+    /// Sets how far the stick has to be pushed before it counts.
+    pub const fn with_inner(mut self, inner: f32) -> Self {
+        self.inner = inner;
+        self
+    }
+
+    #[must_use]
+    /// Sets how far the stick has to be pushed to count as a full push.
+    pub const fn with_outer(mut self, outer: f32) -> Self {
+        self.outer = outer;
+        self
+    }
+
+    /// Applies the dead zone to a stick position. The distance between the
+    /// two zones is stretched back out to 0.0 to 1.0, so the paddle speeds up
+    /// smoothly from a standstill instead of jumping as the stick leaves the
+    /// dead zone.
+    pub fn apply(&self, stick: Vec2) -> Vec2 {
+        let length = stick.length();
+        if length <= self.inner {
+            return Vec2::ZERO;
+        }
+
+        let range = (self.outer - self.inner).max(f32::EPSILON);
+        let scaled = ((length - self.inner) / range).min(1.0);
+        stick / length * scaled
+    }
+}
+
+impl Default for StickDeadZone {
+    fn default() -> Self {
+        Self {
+            inner: STICK_DEAD_ZONE,
+            outer: STICK_OUTER_ZONE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gamepads_are_handed_out_in_order() {
+        let mut assignment = GamepadAssignment::default();
+
+        assert_eq!(assignment.connect(Gamepad::new(3)), Some(Side::Left));
+        assert_eq!(assignment.connect(Gamepad::new(5)), Some(Side::Right));
+        assert_eq!(assignment.connect(Gamepad::new(7)), Some(Side::Top));
+        assert_eq!(assignment.connect(Gamepad::new(9)), Some(Side::Bottom));
+        assert_eq!(assignment.connect(Gamepad::new(11)), None);
+
+        // Reconnecting doesn't move a gamepad to the other side
+        assert_eq!(assignment.connect(Gamepad::new(5)), Some(Side::Right));
+
+        // A freed side goes to the next gamepad that connects
+        assert_eq!(assignment.disconnect(Gamepad::new(3)), Some(Side::Left));
+        assert_eq!(assignment.disconnect(Gamepad::new(3)), None);
+        assert_eq!(assignment.connect(Gamepad::new(11)), Some(Side::Left));
+        assert_eq!(assignment.get(Side::Left), Some(Gamepad::new(11)));
+    }
+
+    #[test]
+    fn test_dead_zone() {
+        let dead_zone = StickDeadZone::default().with_inner(0.2).with_outer(0.8);
+
+        assert_eq!(dead_zone.apply(Vec2::new(0.1, 0.1)), Vec2::ZERO);
+        assert!((dead_zone.apply(Vec2::new(0.0, 0.5)).y - 0.5).abs() < 1e-6);
+        assert_eq!(dead_zone.apply(Vec2::new(-0.9, 0.0)), Vec2::NEG_X);
+    }
+}
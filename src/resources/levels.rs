@@ -0,0 +1,52 @@
+use bevy::{
+    asset::{AssetServer, Assets, Handle},
+    prelude::{FromWorld, Resource, World},
+};
+
+use crate::level::Level;
+
+/// The Breakout levels, in the order they are played.
+#[derive(Resource, Debug, Clone)]
+pub struct Levels {
+    /// The levels being loaded, in the order they are played.
+    pub handles: Vec<Handle<Level>>,
+}
+
+impl Levels {
+    /// The level files shipped with the game, in the order they are played.
+    pub const FILES: [&'static str; 3] = [
+        "levels/01.level.ron",
+        "levels/02.level.ron",
+        "levels/03.level.ron",
+    ];
+
+    /// How many levels there are to clear.
+    pub const fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Whether there are no levels at all.
+    pub const fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    /// The level at position `index`, if it has finished loading.
+    pub fn get<'a>(&self, index: usize, assets: &'a Assets<Level>) -> Option<&'a Level> {
+        self.handles
+            .get(index)
+            .and_then(|handle| assets.get(handle))
+    }
+}
+
+impl FromWorld for Levels {
+    /// Starts loading every level shipped with the game.
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        Self {
+            handles: Self::FILES
+                .iter()
+                .map(|file| asset_server.load(*file))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::paddle::Side,
+    constants::{GAMES_IN_SERIES, LIVES, MAX_SCORE, WIN_MARGIN, WIN_SCORE},
+};
+
+/// Which ball decides the point when more than one ball is in play.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PointEnds {
+    /// The point goes to whoever puts the first ball into a goal.
+    #[default]
+    FirstBall,
+    /// Balls that go into a goal sit out the rest of the point, and it goes
+    /// to whoever puts the last ball in play into a goal.
+    LastBall,
+}
+
+/// How a game is won.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Victory {
+    /// The first player to reach the winning score, far enough ahead of
+    /// everyone else, wins.
+    #[default]
+    Points,
+    /// Every player starts with a number of lives and loses one for each goal
+    /// they let in. Players with none left are knocked out, and the last one
+    /// standing wins.
+    Elimination,
+}
+
+/// The rules that decide who wins a game, and how many games make up a series.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchRules {
+    /// The number of points a player needs to win a game.
+    pub points_to_win: u64,
+    /// How far ahead a player has to be to win a game. With a margin of 2, a
+    /// game tied at the winning score goes to deuce and carries on until
+    /// someone leads by two.
+    pub win_by: u64,
+    /// If set, the first player to reach this many points wins the game no
+    /// matter the margin, so that deuce can't go on forever.
+    pub max_points: Option<u64>,
+    /// How many games are in a series. The series is won by whoever wins more
+    /// than half of them.
+    pub games_in_series: u32,
+    /// Which ball decides the point when more than one ball is in play.
+    pub point_ends: PointEnds,
+    /// How a game is won.
+    pub victory: Victory,
+    /// How many goals a player can let in before they are knocked out, when
+    /// playing for elimination.
+    pub lives: u64,
+}
+
+impl MatchRules {
+    #[must_use]
+    /// Sets the number of points needed to win a game.
+    pub const fn with_points_to_win(mut self, points_to_win: u64) -> Self {
+        self.points_to_win = points_to_win;
+        self
+    }
+
+    #[must_use]
+    /// Sets how far ahead a player has to be to win a game.
+    pub const fn with_win_by(mut self, win_by: u64) -> Self {
+        self.win_by = win_by;
+        self
+    }
+
+    #[must_use]
+    /// Sets the score at which the game ends regardless of the margin.
+    pub const fn with_max_points(mut self, max_points: Option<u64>) -> Self {
+        self.max_points = max_points;
+        self
+    }
+
+    #[must_use]
+    /// Sets how many games are in a series.
+    pub const fn with_games_in_series(mut self, games_in_series: u32) -> Self {
+        self.games_in_series = games_in_series;
+        self
+    }
+
+    #[must_use]
+    /// Sets which ball decides the point when more than one ball is in play.
+    pub const fn with_point_ends(mut self, point_ends: PointEnds) -> Self {
+        self.point_ends = point_ends;
+        self
+    }
+
+    #[must_use]
+    /// Sets how a game is won.
+    pub const fn with_victory(mut self, victory: Victory) -> Self {
+        self.victory = victory;
+        self
+    }
+
+    #[must_use]
+    /// Sets how many goals a player can let in before they are knocked out.
+    pub const fn with_lives(mut self, lives: u64) -> Self {
+        self.lives = lives;
+        self
+    }
+
+    /// What each player's score starts at.
+    pub const fn starting_score(&self) -> u64 {
+        match self.victory {
+            Victory::Points => 0,
+            Victory::Elimination => self.lives,
+        }
+    }
+
+    /// Returns the side that has won the game with the given scores, if any.
+    /// When playing for elimination, the scores are the lives each player has
+    /// left.
+    pub fn game_winner(&self, scores: &[(Side, u64)]) -> Option<Side> {
+        if self.victory == Victory::Elimination {
+            let mut standing = scores.iter().filter(|(_, lives)| *lives > 0);
+            return match (standing.next(), standing.next()) {
+                (Some(&(last, _)), None) => Some(last),
+                _ => None,
+            };
+        }
+
+        let mut ranked = scores.to_vec();
+        ranked.sort_by(|(_, a), (_, b)| b.cmp(a));
+        let (leader, score) = *ranked.first()?;
+        let lead = score - ranked.get(1).map_or(0, |&(_, next)| next);
+
+        if lead == 0 {
+            return None;
+        }
+
+        let reached_cap = self.max_points.is_some_and(|max| score >= max);
+        let won_outright = score >= self.points_to_win && lead >= self.win_by;
+        (won_outright || reached_cap).then_some(leader)
+    }
+
+    /// The number of games a player needs to win the series.
+    pub const fn games_to_win(&self) -> u32 {
+        self.games_in_series / 2 + 1
+    }
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self {
+            points_to_win: WIN_SCORE,
+            win_by: WIN_MARGIN,
+            max_points: MAX_SCORE,
+            games_in_series: GAMES_IN_SERIES,
+            point_ends: PointEnds::default(),
+            victory: Victory::default(),
+            lives: LIVES,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The winner of a two-player game with the given scores.
+    fn winner(rules: &MatchRules, left: u64, right: u64) -> Option<Side> {
+        rules.game_winner(&[(Side::Left, left), (Side::Right, right)])
+    }
+
+    #[test]
+    fn test_first_to_the_winning_score() {
+        let rules = MatchRules::default().with_points_to_win(5).with_win_by(1);
+
+        assert_eq!(winner(&rules, 4, 3), None);
+        assert_eq!(winner(&rules, 5, 4), Some(Side::Left));
+        assert_eq!(winner(&rules, 0, 5), Some(Side::Right));
+
+        // The default rules are plain first to five, with no deuce
+        assert_eq!(winner(&MatchRules::default(), 5, 4), Some(Side::Left));
+    }
+
+    #[test]
+    fn test_deuce() {
+        let rules = MatchRules::default()
+            .with_points_to_win(11)
+            .with_win_by(2)
+            .with_max_points(Some(15));
+
+        assert_eq!(winner(&rules, 11, 10), None);
+        assert_eq!(winner(&rules, 11, 11), None);
+        assert_eq!(winner(&rules, 13, 11), Some(Side::Left));
+        assert_eq!(winner(&rules, 11, 13), Some(Side::Right));
+
+        // The cap ends deuce
+        assert_eq!(winner(&rules, 14, 14), None);
+        assert_eq!(winner(&rules, 14, 15), Some(Side::Right));
+    }
+
+    #[test]
+    fn test_four_players_on_points() {
+        let rules = MatchRules::default().with_points_to_win(5).with_win_by(2);
+        let scores = |top| {
+            [
+                (Side::Left, 3),
+                (Side::Right, 1),
+                (Side::Top, top),
+                (Side::Bottom, 0),
+            ]
+        };
+
+        // Only the nearest challenger counts toward the margin
+        assert_eq!(rules.game_winner(&scores(4)), None);
+        assert_eq!(rules.game_winner(&scores(5)), Some(Side::Top));
+    }
+
+    #[test]
+    fn test_last_one_standing() {
+        let rules = MatchRules::default().with_victory(Victory::Elimination);
+
+        assert_eq!(
+            rules.game_winner(&[(Side::Left, 2), (Side::Right, 0), (Side::Top, 1)]),
+            None
+        );
+        assert_eq!(
+            rules.game_winner(&[(Side::Left, 0), (Side::Right, 0), (Side::Top, 1)]),
+            Some(Side::Top)
+        );
+        assert_eq!(rules.starting_score(), LIVES);
+    }
+
+    #[test]
+    fn test_games_to_win() {
+        assert_eq!(
+            MatchRules::default().with_games_in_series(1).games_to_win(),
+            1
+        );
+        assert_eq!(
+            MatchRules::default().with_games_in_series(3).games_to_win(),
+            2
+        );
+        assert_eq!(
+            MatchRules::default().with_games_in_series(5).games_to_win(),
+            3
+        );
+    }
+}
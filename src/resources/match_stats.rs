@@ -0,0 +1,65 @@
+use bevy::prelude::Resource;
+
+use crate::component::paddle::Side;
+
+use super::RallySpeed;
+
+/// Running stats for the current match, shown on the results screen.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct MatchStats {
+    /// How many points have been played, across every game of the series.
+    pub points_played: u32,
+    /// The most paddle hits in a single rally.
+    pub longest_rally: u32,
+    /// The fastest the ball went.
+    pub top_speed: f32,
+}
+
+impl MatchStats {
+    /// Takes note of how the current rally is going.
+    pub fn record_rally(&mut self, rally: &RallySpeed) {
+        self.longest_rally = self.longest_rally.max(rally.hits);
+        self.top_speed = self.top_speed.max(rally.speed);
+    }
+
+    /// Starts counting from scratch for a new match.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// How the last match ended.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct MatchResult {
+    /// The side that won the match.
+    pub winner: Side,
+    /// Every player's score in the final game, in the order the sides are
+    /// numbered.
+    pub scores: Vec<(Side, u64)>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_record_rally() {
+        let mut stats = MatchStats::default();
+        let mut rally = RallySpeed::default()
+            .with_serve_speed(50.0)
+            .with_hit_multiplier(2.0);
+
+        rally.hit(50.0);
+        rally.hit(100.0);
+        stats.record_rally(&rally);
+        rally.reset();
+        rally.hit(50.0);
+        stats.record_rally(&rally);
+
+        assert_eq!(stats.longest_rally, 2);
+        assert_eq!(stats.top_speed, 200.0);
+
+        stats.reset();
+        assert_eq!(stats, MatchStats::default());
+    }
+}
@@ -0,0 +1,52 @@
+/// The arenas that can be played, and which one is picked.
+pub mod arenas;
+/// The keys each player uses.
+pub mod bindings;
+/// How a game of Breakout is going, and the high score.
+pub mod breakout;
+/// Controls how the ball bounces off paddles.
+pub mod deflection;
+/// Which gamepads play which side, and how their sticks are read.
+pub mod gamepads;
+/// The Breakout levels, in the order they are played.
+pub mod levels;
+/// The rules for winning games and series.
+pub mod match_rules;
+/// Stats and results from a match.
+pub mod match_stats;
+/// How many balls are served at once.
+pub mod multiball;
+/// Who the player is up against.
+pub mod opponent;
+/// The power-ups that can turn up on the court.
+pub mod power_ups;
+/// The player's personal best at wall practice.
+pub mod practice;
+/// Tracks and controls the ball's speed during a rally.
+pub mod rally_speed;
+/// Tracks games won across a series.
+pub mod series;
+/// Controls how the ball is served.
+pub mod serve;
+/// Everything the player can tune, saved in their config file.
+pub mod settings;
+/// Who plays alongside the left player in doubles.
+pub mod teammate;
+
+pub use arenas::Arenas;
+pub use bindings::Bindings;
+pub use breakout::Breakout;
+pub use deflection::Deflection;
+pub use gamepads::{GamepadAssignment, StickDeadZone};
+pub use levels::Levels;
+pub use match_rules::{MatchRules, PointEnds, Victory};
+pub use match_stats::{MatchResult, MatchStats};
+pub use multiball::Multiball;
+pub use opponent::Opponent;
+pub use power_ups::PowerUps;
+pub use practice::Practice;
+pub use rally_speed::RallySpeed;
+pub use series::Series;
+pub use serve::{Serve, ServeRule};
+pub use settings::Settings;
+pub use teammate::Teammate;
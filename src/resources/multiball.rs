@@ -0,0 +1,32 @@
+use bevy::prelude::Resource;
+
+use crate::constants::MULTIBALL_BALLS;
+
+/// Whether matches are played with more than one ball at a time.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Multiball {
+    /// Whether multiball mode is on.
+    pub enabled: bool,
+    /// How many balls are served at once when it is.
+    pub balls: usize,
+}
+
+impl Multiball {
+    /// How many balls are served at the start of each point.
+    pub const fn balls_in_play(&self) -> usize {
+        if self.enabled {
+            self.balls
+        } else {
+            1
+        }
+    }
+}
+
+impl Default for Multiball {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            balls: MULTIBALL_BALLS,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use bevy::prelude::Resource;
+
+use crate::component::ai::Difficulty;
+
+/// Who controls the right paddle, or whether the player practises on their
+/// own against a wall.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub enum Opponent {
+    /// A second player at the keyboard.
+    #[default]
+    Human,
+    /// The computer, at the given difficulty.
+    Cpu(Difficulty),
+    /// Nobody: the other sides of the court are walled up, and the player
+    /// keeps the rally going as long as they can.
+    Wall,
+}
+
+impl Opponent {
+    /// Every opponent that can be picked from the main menu, in order.
+    pub const CHOICES: [Self; 5] = [
+        Self::Human,
+        Self::Cpu(Difficulty::EASY),
+        Self::Cpu(Difficulty::NORMAL),
+        Self::Cpu(Difficulty::HARD),
+        Self::Wall,
+    ];
+
+    /// The next opponent in the main menu, wrapping around.
+    #[must_use]
+    pub fn next(&self) -> Self {
+        let index = Self::CHOICES.iter().position(|o| o == self).unwrap_or(0);
+        Self::CHOICES[(index + 1) % Self::CHOICES.len()]
+    }
+
+    /// The previous opponent in the main menu, wrapping around.
+    #[must_use]
+    pub fn previous(&self) -> Self {
+        let index = Self::CHOICES.iter().position(|o| o == self).unwrap_or(0);
+        Self::CHOICES[(index + Self::CHOICES.len() - 1) % Self::CHOICES.len()]
+    }
+
+    /// A short name for the opponent, for the main menu.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Human => "2 Players",
+            Self::Cpu(d) if *d == Difficulty::EASY => "CPU - Easy",
+            Self::Cpu(d) if *d == Difficulty::HARD => "CPU - Hard",
+            Self::Cpu(d) if *d == Difficulty::NORMAL => "CPU - Normal",
+            Self::Cpu(_) => "CPU - Custom",
+            Self::Wall => "Wall practice",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cycle_choices() {
+        let opponent = Opponent::default();
+        assert_eq!(opponent.next(), Opponent::Cpu(Difficulty::EASY));
+        assert_eq!(opponent.previous(), Opponent::Wall);
+        assert_eq!(opponent.previous().next(), opponent);
+        assert_eq!(Opponent::Cpu(Difficulty::HARD).name(), "CPU - Hard");
+        assert_eq!(Opponent::Wall.previous(), Opponent::Cpu(Difficulty::HARD));
+    }
+}
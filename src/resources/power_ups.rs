@@ -0,0 +1,122 @@
+use bevy::prelude::Resource;
+
+use crate::{
+    component::power_up::Kind,
+    constants::{MAX_POWER_UPS_ON_COURT, POWER_UP_DURATION, POWER_UP_INTERVAL, POWER_UP_LIFETIME},
+};
+
+/// A power-up that can turn up on the court.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Entry {
+    /// What the power-up does.
+    pub kind: Kind,
+    /// How likely the power-up is to turn up, compared to the others.
+    pub weight: f32,
+    /// How many seconds its effect lasts.
+    pub duration: f32,
+}
+
+/// Every power-up that can turn up on the court, and how often they do.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct PowerUps {
+    /// Whether power-ups turn up at all. They're off unless the player turns
+    /// them on.
+    pub enabled: bool,
+    /// How many seconds apart power-ups turn up.
+    pub every: f32,
+    /// How many seconds a power-up waits on the court to be collected.
+    pub lifetime: f32,
+    /// The most power-ups that can be waiting on the court at once.
+    pub max_on_court: usize,
+    entries: Vec<Entry>,
+}
+
+impl PowerUps {
+    /// A registry with no power-ups in it, turned off.
+    pub const fn empty() -> Self {
+        Self {
+            enabled: false,
+            every: POWER_UP_INTERVAL,
+            lifetime: POWER_UP_LIFETIME,
+            max_on_court: MAX_POWER_UPS_ON_COURT,
+            entries: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    /// Adds a power-up, or replaces the one of the same kind.
+    pub fn register(mut self, kind: Kind, weight: f32, duration: f32) -> Self {
+        self.entries.retain(|entry| entry.kind != kind);
+        self.entries.push(Entry {
+            kind,
+            weight,
+            duration,
+        });
+        self
+    }
+
+    /// The power-up of the given kind, if it is registered.
+    pub fn get(&self, kind: Kind) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.kind == kind)
+    }
+
+    /// Picks a power-up by weight. `roll` goes from 0.0 to 1.0, and picks
+    /// further down the list the higher it is. Returns `None` if nothing can
+    /// turn up.
+    pub fn pick(&self, roll: f32) -> Option<&Entry> {
+        let total: f32 = self.entries.iter().map(|entry| entry.weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut left = roll * total;
+        self.entries
+            .iter()
+            .filter(|entry| entry.weight > 0.0)
+            .find(|entry| {
+                left -= entry.weight;
+                left < 0.0
+            })
+            .or_else(|| self.entries.iter().rev().find(|entry| entry.weight > 0.0))
+    }
+}
+
+impl Default for PowerUps {
+    /// Every kind of power-up, equally likely.
+    fn default() -> Self {
+        Self::empty()
+            .register(Kind::Enlarge, 1.0, POWER_UP_DURATION)
+            .register(Kind::Shrink, 1.0, POWER_UP_DURATION)
+            .register(Kind::SpeedUp, 1.0, POWER_UP_DURATION)
+            .register(Kind::SlowDown, 1.0, POWER_UP_DURATION)
+            // Takes effect straight away, so it doesn't last
+            .register(Kind::Multiball, 1.0, 0.0)
+            .register(Kind::Sticky, 1.0, POWER_UP_DURATION)
+            .register(Kind::Reverse, 1.0, POWER_UP_DURATION)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pick_by_weight() {
+        let power_ups = PowerUps::empty()
+            .register(Kind::Enlarge, 1.0, 5.0)
+            .register(Kind::Multiball, 0.0, 5.0)
+            .register(Kind::Shrink, 3.0, 5.0);
+
+        assert_eq!(power_ups.pick(0.0).unwrap().kind, Kind::Enlarge);
+        assert_eq!(power_ups.pick(0.2).unwrap().kind, Kind::Enlarge);
+        assert_eq!(power_ups.pick(0.3).unwrap().kind, Kind::Shrink);
+        assert_eq!(power_ups.pick(1.0).unwrap().kind, Kind::Shrink);
+
+        // Registering a kind again replaces it
+        let power_ups = power_ups.register(Kind::Shrink, 0.0, 5.0);
+        assert_eq!(power_ups.pick(0.9).unwrap().kind, Kind::Enlarge);
+        assert_eq!(power_ups.get(Kind::Shrink).unwrap().weight, 0.0);
+
+        assert!(PowerUps::empty().pick(0.5).is_none());
+    }
+}
@@ -0,0 +1,72 @@
+use bevy::prelude::{warn, Resource};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+/// The name of the file the personal best is saved in.
+const FILE_NAME: &str = "practice.ron";
+
+/// How wall practice is going: the longest rally the player has ever kept
+/// going against the wall, which is saved in their config file, and how the
+/// last run went.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Practice {
+    /// The longest rally the player has kept going, in hits.
+    pub best: u32,
+    /// How many hits the last run lasted.
+    #[serde(skip)]
+    pub last: u32,
+    /// Whether the last run set a new personal best.
+    #[serde(skip)]
+    pub new_best: bool,
+}
+
+impl Practice {
+    /// Loads the personal best from the player's config file, starting from
+    /// scratch if there isn't one.
+    pub fn load() -> Self {
+        config::load_or_default(FILE_NAME)
+    }
+
+    /// Saves the personal best to the player's config file.
+    pub fn save(&self) {
+        if let Err(e) = config::save(FILE_NAME, self) {
+            warn!("Couldn't save the personal best: {}", e);
+        }
+    }
+
+    /// Records a run that lasted `hits` hits, keeping it as the personal best
+    /// if it beats it.
+    pub fn finish(&mut self, hits: u32) {
+        self.last = hits;
+        self.new_best = hits > self.best;
+        self.best = self.best.max(hits);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_personal_best() {
+        let mut practice = Practice::default();
+
+        practice.finish(12);
+        assert!(practice.new_best);
+        practice.finish(7);
+        assert!(!practice.new_best);
+        assert_eq!((practice.best, practice.last), (12, 7));
+
+        // Only the best is saved
+        let text = config::to_string(&practice).unwrap();
+        assert_eq!(
+            config::from_str::<Practice>(&text).unwrap(),
+            Practice {
+                best: 12,
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::Resource;
+
+use crate::constants::{BALL_SPEED_UP_PER_HIT, DEFAULT_BALL_SPEED, MAX_BALL_SPEED};
+
+/// Tracks how fast the ball is going during the current rally.
+///
+/// The ball speeds up every time a paddle hits it, up to a limit, and goes
+/// back to its serving speed when someone scores. Anything that wants to react
+/// to the pace of a rally, like the HUD or audio, can read it from here.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct RallySpeed {
+    /// The ball's current speed.
+    pub speed: f32,
+    /// How many times a paddle has hit the ball during this rally.
+    pub hits: u32,
+    /// The speed the ball is served at.
+    pub serve_speed: f32,
+    /// The ball's speed is multiplied by this every time a paddle hits it.
+    pub hit_multiplier: f32,
+    /// The fastest the ball can go.
+    pub max_speed: f32,
+}
+
+impl RallySpeed {
+    #[must_use]
+    /// Sets the speed the ball is served at.
+    pub const fn with_serve_speed(mut self, serve_speed: f32) -> Self {
+        self.serve_speed = serve_speed;
+        self.speed = serve_speed;
+        self
+    }
+
+    #[must_use]
+    /// Sets how much the ball speeds up every time a paddle hits it.
+    pub const fn with_hit_multiplier(mut self, hit_multiplier: f32) -> Self {
+        self.hit_multiplier = hit_multiplier;
+        self
+    }
+
+    #[must_use]
+    /// Sets the fastest the ball can go.
+    pub const fn with_max_speed(mut self, max_speed: f32) -> Self {
+        self.max_speed = max_speed;
+        self
+    }
+
+    /// Records a paddle hit. `speed` is how fast the ball is leaving the
+    /// paddle; returns how fast it should go after speeding up.
+    pub fn hit(&mut self, speed: f32) -> f32 {
+        self.hits += 1;
+        self.speed = (speed * self.hit_multiplier).min(self.max_speed);
+        self.speed
+    }
+
+    /// Starts a new rally at the serving speed.
+    pub const fn reset(&mut self) {
+        self.hits = 0;
+        self.speed = self.serve_speed;
+    }
+}
+
+impl Default for RallySpeed {
+    fn default() -> Self {
+        Self {
+            speed: DEFAULT_BALL_SPEED,
+            hits: 0,
+            serve_speed: DEFAULT_BALL_SPEED,
+            hit_multiplier: BALL_SPEED_UP_PER_HIT,
+            max_speed: MAX_BALL_SPEED,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_speeds_up_to_the_limit() {
+        let mut rally = RallySpeed::default()
+            .with_serve_speed(50.0)
+            .with_hit_multiplier(2.0)
+            .with_max_speed(300.0);
+
+        assert_eq!(rally.hit(50.0), 100.0);
+        assert_eq!(rally.hit(100.0), 200.0);
+        assert_eq!(rally.hit(200.0), 300.0);
+        assert_eq!(rally.hit(300.0), 300.0);
+        assert_eq!(rally.hits, 4);
+        assert_eq!(rally.speed, 300.0);
+
+        rally.reset();
+        assert_eq!(rally.hits, 0);
+        assert_eq!(rally.speed, 50.0);
+    }
+}
@@ -0,0 +1,75 @@
+use bevy::prelude::Resource;
+
+use crate::component::paddle::Side;
+
+use super::MatchRules;
+
+/// How many games each side has won in the current series. This outlives the
+/// individual games, which are torn down and set up again between each one.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Series {
+    /// Games won by the left player.
+    pub left: u32,
+    /// Games won by the right player.
+    pub right: u32,
+    /// Games won by the top player, in four-player arenas.
+    pub top: u32,
+    /// Games won by the bottom player, in four-player arenas.
+    pub bottom: u32,
+}
+
+impl Series {
+    /// Records a game won by `side`.
+    pub const fn record_win(&mut self, side: Side) {
+        match side {
+            Side::Left => self.left += 1,
+            Side::Right => self.right += 1,
+            Side::Top => self.top += 1,
+            Side::Bottom => self.bottom += 1,
+        }
+    }
+
+    /// The number of games `side` has won.
+    pub const fn wins(&self, side: Side) -> u32 {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+            Side::Top => self.top,
+            Side::Bottom => self.bottom,
+        }
+    }
+
+    /// Returns the side that has won the series under the given rules, if any.
+    pub fn winner(&self, rules: &MatchRules) -> Option<Side> {
+        Side::ALL
+            .into_iter()
+            .find(|&side| self.wins(side) >= rules.games_to_win())
+    }
+
+    /// Starts a new series.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_best_of_three() {
+        let rules = MatchRules::default().with_games_in_series(3);
+        let mut series = Series::default();
+
+        series.record_win(Side::Left);
+        assert_eq!(series.winner(&rules), None);
+        series.record_win(Side::Right);
+        assert_eq!(series.winner(&rules), None);
+        series.record_win(Side::Right);
+        assert_eq!(series.winner(&rules), Some(Side::Right));
+        assert_eq!(series.wins(Side::Left), 1);
+
+        series.reset();
+        assert_eq!(series, Series::default());
+    }
+}
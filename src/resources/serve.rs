@@ -0,0 +1,160 @@
+use bevy::prelude::{Resource, Vec2};
+
+use crate::{
+    component::paddle::Side,
+    constants::{MAX_SERVE_ANGLE, SERVE_COUNTDOWN},
+};
+
+/// Decides which side receives the next serve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServeRule {
+    /// The ball is served toward the player who just conceded a point.
+    ToConceder,
+    /// The receiving side switches after every `every` points, starting with
+    /// whichever side received the first serve of the match.
+    Alternate {
+        /// How many points in a row are served toward the same side.
+        every: u32,
+    },
+}
+
+/// How the ball is served, and who is receiving.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct Serve {
+    /// How long the ball waits at the centre before it is served, in seconds.
+    pub countdown: f32,
+    /// The steepest angle either side of straight at the receiver, in
+    /// radians, that the ball can be served at.
+    pub max_angle: f32,
+    /// Decides which side receives each serve.
+    pub rule: ServeRule,
+    /// The side that received the first serve of the match.
+    pub first: Side,
+    /// How many points have been played this match.
+    pub points: u32,
+}
+
+impl Serve {
+    #[must_use]
+    /// Sets how long the ball waits before it is served.
+    pub const fn with_countdown(mut self, countdown: f32) -> Self {
+        self.countdown = countdown;
+        self
+    }
+
+    #[must_use]
+    /// Sets the steepest angle the ball can be served at.
+    pub const fn with_max_angle(mut self, max_angle: f32) -> Self {
+        self.max_angle = max_angle;
+        self
+    }
+
+    #[must_use]
+    /// Sets the rule that decides which side receives each serve.
+    pub const fn with_rule(mut self, rule: ServeRule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// Starts a new match with the first serve going toward `first`.
+    pub const fn start_match(&mut self, first: Side) {
+        self.first = first;
+        self.points = 0;
+    }
+
+    /// Records that `conceded` lost a point, and returns the side that
+    /// receives the next serve.
+    pub fn next(&mut self, conceded: Side) -> Side {
+        self.points += 1;
+        match self.rule {
+            ServeRule::ToConceder => conceded,
+            ServeRule::Alternate { every } => {
+                if (self.points / every.max(1)).is_multiple_of(2) {
+                    self.first
+                } else {
+                    self.first.opposite()
+                }
+            }
+        }
+    }
+
+    /// Picks a random direction to serve toward `toward`, within `max_angle`
+    /// either side of straight at it.
+    pub fn direction(&self, toward: Side) -> Vec2 {
+        let angle = rand::random::<f32>().mul_add(2.0, -1.0) * self.max_angle;
+        (-toward.inward()).rotate(Vec2::from_angle(angle))
+    }
+}
+
+impl Default for Serve {
+    fn default() -> Self {
+        Self {
+            countdown: SERVE_COUNTDOWN,
+            max_angle: MAX_SERVE_ANGLE,
+            rule: ServeRule::ToConceder,
+            first: Side::default(),
+            points: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_serve_toward_conceder() {
+        let mut serve = Serve::default();
+        serve.start_match(Side::Right);
+
+        assert_eq!(serve.next(Side::Left), Side::Left);
+        assert_eq!(serve.next(Side::Left), Side::Left);
+        assert_eq!(serve.next(Side::Right), Side::Right);
+    }
+
+    #[test]
+    fn test_serve_alternates() {
+        let mut serve = Serve::default().with_rule(ServeRule::Alternate { every: 2 });
+        serve.start_match(Side::Right);
+
+        let receivers: Vec<Side> = (0..6).map(|_| serve.next(Side::Left)).collect();
+        assert_eq!(
+            receivers,
+            [
+                Side::Right,
+                Side::Left,
+                Side::Left,
+                Side::Right,
+                Side::Right,
+                Side::Left
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serve_direction_is_within_the_cone() {
+        let serve = Serve::default().with_max_angle(0.5);
+        let (mut up, mut down) = (false, false);
+
+        for _ in 0..200 {
+            let left = serve.direction(Side::Left);
+            assert!(left.x < 0.0);
+            assert!(left.angle_between(Vec2::NEG_X).abs() <= 0.5 + 1e-5);
+            assert!((left.length() - 1.0).abs() < 1e-5);
+
+            let right = serve.direction(Side::Right);
+            assert!(right.x > 0.0);
+            assert!(right.angle_between(Vec2::X).abs() <= 0.5 + 1e-5);
+
+            let top = serve.direction(Side::Top);
+            assert!(top.y > 0.0);
+            assert!(top.angle_between(Vec2::Y).abs() <= 0.5 + 1e-5);
+
+            up |= right.y > 0.0;
+            down |= right.y < 0.0;
+        }
+
+        // Serves go both up and down
+        assert!(up && down);
+    }
+}
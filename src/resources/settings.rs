@@ -0,0 +1,492 @@
+use std::fmt;
+
+use bevy::{
+    prelude::{info, warn, Resource},
+    window::WindowMode,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config,
+    constants::{
+        BALL_SPEED_UP_PER_HIT, DEFAULT_BALL_SPEED, EFFECTS_VOLUME, MAX_BALL_SPEED, MULTIBALL_BALLS,
+        MUSIC_VOLUME, PADDLE_DEFAULT_FRICTION, PADDLE_SPEED_MULTIPLIER, POWER_UP_INTERVAL,
+        SERVE_COUNTDOWN,
+    },
+    plugins::window_scaling_2d::constants::ASPECT_RATIO_4_3,
+};
+
+use super::MatchRules;
+
+/// The name of the config file the settings are saved in.
+const FILE_NAME: &str = "settings.ron";
+
+/// The version of the settings file this build writes. Bump it whenever the
+/// meaning of an existing setting changes, and teach `Settings::migrate` how
+/// to bring older files up to date.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// Everything the player can tune without recompiling the game.
+///
+/// These are loaded from the player's config file at startup. Anything
+/// missing from the file, or that doesn't make sense, falls back to the
+/// defaults in `constants`.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The version of the settings file these were loaded from.
+    pub version: u32,
+    /// The rules for winning games and series.
+    pub rules: MatchRules,
+    /// How the paddles move.
+    pub paddle: PaddleSettings,
+    /// How the ball moves.
+    pub ball: BallSettings,
+    /// How loud things are.
+    pub audio: AudioSettings,
+    /// How the window is shown.
+    pub video: VideoSettings,
+    /// Whether and how often power-ups turn up.
+    pub power_ups: PowerUpSettings,
+    /// Whether matches are played with more than one ball at a time.
+    pub multiball: MultiballSettings,
+}
+
+/// How the paddles move.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaddleSettings {
+    /// The fastest a paddle can move.
+    pub speed: f32,
+    /// How quickly a paddle slows down once it is let go.
+    pub friction: f32,
+}
+
+/// How the ball moves.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BallSettings {
+    /// The speed the ball is served at.
+    pub serve_speed: f32,
+    /// The ball's speed is multiplied by this every time a paddle hits it.
+    pub speed_up_per_hit: f32,
+    /// The fastest the ball can go.
+    pub max_speed: f32,
+    /// How many seconds the ball waits before it is served.
+    pub serve_countdown: f32,
+}
+
+/// How loud things are. Volumes go from 0.0 (silent) to 1.0.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    /// How loud the background music is.
+    pub music_volume: f32,
+    /// How loud the sound effects are.
+    pub effects_volume: f32,
+}
+
+/// How the window is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VideoSettings {
+    /// Whether the game is played in a window or full screen.
+    pub window_mode: WindowMode,
+    /// The width of the window divided by its height. If `None`, the window
+    /// can be resized freely.
+    pub aspect_ratio: Option<f32>,
+}
+
+/// Whether and how often power-ups turn up.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerUpSettings {
+    /// Whether power-ups turn up at all. Off by default.
+    pub enabled: bool,
+    /// How many seconds apart power-ups turn up.
+    pub every: f32,
+}
+
+/// Whether matches are played with more than one ball at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MultiballSettings {
+    /// Whether multiball mode is on.
+    pub enabled: bool,
+    /// How many balls are served at once when it is.
+    pub balls: usize,
+}
+
+/// A setting that was out of range when the settings were loaded. It is
+/// replaced with its default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Invalid {
+    /// The name of the setting, as it appears in the file.
+    pub setting: &'static str,
+    /// What the setting has to be.
+    pub requirement: &'static str,
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} must be {}", self.setting, self.requirement)
+    }
+}
+
+/// Checks a setting, putting its default back if it doesn't meet the
+/// requirement.
+fn check<T: Copy>(
+    problems: &mut Vec<Invalid>,
+    value: &mut T,
+    default: T,
+    valid: impl FnOnce(T) -> bool,
+    setting: &'static str,
+    requirement: &'static str,
+) {
+    if !valid(*value) {
+        problems.push(Invalid {
+            setting,
+            requirement,
+        });
+        *value = default;
+    }
+}
+
+/// Whether a number is finite and greater than zero.
+fn positive(value: f32) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+/// Whether a number is between 0.0 and 1.0.
+fn unit(value: f32) -> bool {
+    (0.0..=1.0).contains(&value)
+}
+
+impl Settings {
+    /// Loads the settings from the player's config file. Settings that are
+    /// missing or out of range fall back to their defaults, with a warning for
+    /// each one that was out of range. If there's no file yet, the defaults
+    /// are used, and nothing is written until a setting is changed.
+    pub fn load() -> Self {
+        match config::load::<Self>(FILE_NAME) {
+            Ok(Some(settings)) => {
+                let (settings, problems) = settings.migrate().validated();
+                for problem in problems {
+                    warn!("Invalid setting in {}: {}", FILE_NAME, problem);
+                }
+                settings
+            }
+            Ok(None) => {
+                info!("No {} yet, using the defaults", FILE_NAME);
+                Self::default()
+            }
+            Err(e) => {
+                warn!("Couldn't read {}, using the defaults: {}", FILE_NAME, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Saves the settings to the player's config file. This only happens once
+    /// the player changes a setting in the game.
+    pub fn save(&self) {
+        if let Err(e) = config::save(FILE_NAME, self) {
+            warn!("Couldn't save the settings: {}", e);
+        }
+    }
+
+    /// Brings settings loaded from an older version of the file up to date.
+    /// Settings from a newer version are kept as they are, as far as this
+    /// version understands them.
+    #[must_use]
+    pub fn migrate(mut self) -> Self {
+        if self.version > SETTINGS_VERSION {
+            warn!(
+                "Settings are from a newer version ({} > {}), some may be ignored",
+                self.version, SETTINGS_VERSION
+            );
+        }
+        // Nothing has changed meaning since the first version
+        self.version = SETTINGS_VERSION;
+        self
+    }
+
+    /// Puts back the default for every setting that is out of range, and
+    /// returns what was wrong with them.
+    #[must_use]
+    pub fn validated(mut self) -> (Self, Vec<Invalid>) {
+        let defaults = Self::default();
+        let mut problems = Vec::new();
+        let p = &mut problems;
+
+        let rules = &mut self.rules;
+        check(
+            p,
+            &mut rules.points_to_win,
+            defaults.rules.points_to_win,
+            |v| v > 0,
+            "rules.points_to_win",
+            "at least 1",
+        );
+        check(
+            p,
+            &mut rules.win_by,
+            defaults.rules.win_by,
+            |v| v > 0,
+            "rules.win_by",
+            "at least 1",
+        );
+        let points_to_win = rules.points_to_win;
+        check(
+            p,
+            &mut rules.max_points,
+            defaults.rules.max_points,
+            |v| v.is_none_or(|max| max >= points_to_win),
+            "rules.max_points",
+            "at least rules.points_to_win",
+        );
+        check(
+            p,
+            &mut rules.games_in_series,
+            defaults.rules.games_in_series,
+            |v| v > 0,
+            "rules.games_in_series",
+            "at least 1",
+        );
+        check(
+            p,
+            &mut rules.lives,
+            defaults.rules.lives,
+            |v| v > 0,
+            "rules.lives",
+            "at least 1",
+        );
+
+        check(
+            p,
+            &mut self.paddle.speed,
+            defaults.paddle.speed,
+            positive,
+            "paddle.speed",
+            "greater than 0",
+        );
+        check(
+            p,
+            &mut self.paddle.friction,
+            defaults.paddle.friction,
+            |v| v.is_finite() && v >= 0.0,
+            "paddle.friction",
+            "0 or more",
+        );
+
+        let ball = &mut self.ball;
+        check(
+            p,
+            &mut ball.serve_speed,
+            defaults.ball.serve_speed,
+            positive,
+            "ball.serve_speed",
+            "greater than 0",
+        );
+        check(
+            p,
+            &mut ball.speed_up_per_hit,
+            defaults.ball.speed_up_per_hit,
+            |v| v.is_finite() && v >= 1.0,
+            "ball.speed_up_per_hit",
+            "1 or more",
+        );
+        let serve_speed = ball.serve_speed;
+        check(
+            p,
+            &mut ball.max_speed,
+            defaults.ball.max_speed.max(serve_speed),
+            |v| v >= serve_speed,
+            "ball.max_speed",
+            "at least ball.serve_speed",
+        );
+        check(
+            p,
+            &mut ball.serve_countdown,
+            defaults.ball.serve_countdown,
+            |v| v.is_finite() && v >= 0.0,
+            "ball.serve_countdown",
+            "0 or more",
+        );
+
+        check(
+            p,
+            &mut self.audio.music_volume,
+            defaults.audio.music_volume,
+            unit,
+            "audio.music_volume",
+            "between 0 and 1",
+        );
+        check(
+            p,
+            &mut self.audio.effects_volume,
+            defaults.audio.effects_volume,
+            unit,
+            "audio.effects_volume",
+            "between 0 and 1",
+        );
+
+        check(
+            p,
+            &mut self.video.aspect_ratio,
+            defaults.video.aspect_ratio,
+            |v| v.is_none_or(positive),
+            "video.aspect_ratio",
+            "greater than 0",
+        );
+
+        check(
+            p,
+            &mut self.power_ups.every,
+            defaults.power_ups.every,
+            positive,
+            "power_ups.every",
+            "greater than 0",
+        );
+
+        check(
+            p,
+            &mut self.multiball.balls,
+            defaults.multiball.balls,
+            |v| v > 0,
+            "multiball.balls",
+            "at least 1",
+        );
+
+        (self, problems)
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            rules: MatchRules::default(),
+            paddle: PaddleSettings::default(),
+            ball: BallSettings::default(),
+            audio: AudioSettings::default(),
+            video: VideoSettings::default(),
+            power_ups: PowerUpSettings::default(),
+            multiball: MultiballSettings::default(),
+        }
+    }
+}
+
+impl Default for PaddleSettings {
+    fn default() -> Self {
+        Self {
+            speed: PADDLE_SPEED_MULTIPLIER,
+            friction: PADDLE_DEFAULT_FRICTION,
+        }
+    }
+}
+
+impl Default for BallSettings {
+    fn default() -> Self {
+        Self {
+            serve_speed: DEFAULT_BALL_SPEED,
+            speed_up_per_hit: BALL_SPEED_UP_PER_HIT,
+            max_speed: MAX_BALL_SPEED,
+            serve_countdown: SERVE_COUNTDOWN,
+        }
+    }
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            music_volume: MUSIC_VOLUME,
+            effects_volume: EFFECTS_VOLUME,
+        }
+    }
+}
+
+impl Default for VideoSettings {
+    fn default() -> Self {
+        Self {
+            window_mode: WindowMode::Windowed,
+            aspect_ratio: Some(*ASPECT_RATIO_4_3),
+        }
+    }
+}
+
+impl Default for PowerUpSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            every: POWER_UP_INTERVAL,
+        }
+    }
+}
+
+impl Default for MultiballSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            balls: MULTIBALL_BALLS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_missing_settings_fall_back_to_the_defaults() {
+        let settings: Settings =
+            config::from_str("(rules: (points_to_win: 11), audio: (music_volume: 0.5))").unwrap();
+
+        assert_eq!(settings.rules.points_to_win, 11);
+        assert_eq!(settings.rules.win_by, MatchRules::default().win_by);
+        assert_eq!(settings.audio.music_volume, 0.5);
+        assert_eq!(settings.ball, BallSettings::default());
+        // Power-ups have to be turned on
+        assert!(!settings.power_ups.enabled);
+        // A file without a version is taken to be the current one
+        assert_eq!(settings.version, SETTINGS_VERSION);
+    }
+
+    #[test]
+    fn test_invalid_settings_are_reported_and_replaced() {
+        let mut settings = Settings::default();
+        settings.paddle.speed = -3.0;
+        settings.audio.effects_volume = 2.0;
+        settings.ball.serve_speed = 100.0;
+        settings.ball.max_speed = 80.0;
+
+        let (settings, problems) = settings.validated();
+        let names: Vec<_> = problems.iter().map(|p| p.setting).collect();
+        assert_eq!(
+            names,
+            ["paddle.speed", "ball.max_speed", "audio.effects_volume"]
+        );
+        assert_eq!(settings.paddle.speed, PADDLE_SPEED_MULTIPLIER);
+        assert_eq!(settings.audio.effects_volume, EFFECTS_VOLUME);
+        // The max speed can't fall back below the serve speed it was checked
+        // against
+        assert_eq!(settings.ball.max_speed, MAX_BALL_SPEED.max(100.0));
+        assert_eq!(
+            problems[0].to_string(),
+            "paddle.speed must be greater than 0"
+        );
+
+        assert!(Settings::default().validated().1.is_empty());
+    }
+
+    #[test]
+    fn test_settings_survive_a_round_trip() {
+        let mut settings = Settings::default();
+        settings.video.window_mode = WindowMode::BorderlessFullscreen;
+        settings.video.aspect_ratio = None;
+        settings.rules.max_points = Some(9);
+
+        let text = config::to_string(&settings).unwrap();
+        assert_eq!(config::from_str::<Settings>(&text).unwrap(), settings);
+    }
+}
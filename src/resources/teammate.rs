@@ -0,0 +1,50 @@
+use bevy::prelude::Resource;
+
+use crate::component::ai::Difficulty;
+
+/// Who plays alongside the left player, on arenas where each side has an
+/// attacker as well as a defender.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub enum Teammate {
+    /// Another player, on the top player's keys or gamepad.
+    #[default]
+    Human,
+    /// The computer, at the given difficulty.
+    Cpu(Difficulty),
+}
+
+impl Teammate {
+    /// Every teammate that can be picked from the main menu, in order.
+    pub const CHOICES: [Self; 4] = [
+        Self::Human,
+        Self::Cpu(Difficulty::EASY),
+        Self::Cpu(Difficulty::NORMAL),
+        Self::Cpu(Difficulty::HARD),
+    ];
+
+    /// The next teammate in the main menu, wrapping around.
+    #[must_use]
+    pub fn next(&self) -> Self {
+        let index = Self::CHOICES.iter().position(|t| t == self).unwrap_or(0);
+        Self::CHOICES[(index + 1) % Self::CHOICES.len()]
+    }
+
+    /// How well the computer plays as the teammate, if it does.
+    pub const fn difficulty(&self) -> Option<Difficulty> {
+        match self {
+            Self::Human => None,
+            Self::Cpu(difficulty) => Some(*difficulty),
+        }
+    }
+
+    /// A short name for the teammate, for the main menu.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Human => "Human",
+            Self::Cpu(d) if *d == Difficulty::EASY => "CPU - Easy",
+            Self::Cpu(d) if *d == Difficulty::HARD => "CPU - Hard",
+            Self::Cpu(d) if *d == Difficulty::NORMAL => "CPU - Normal",
+            Self::Cpu(_) => "CPU - Custom",
+        }
+    }
+}
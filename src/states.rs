@@ -7,6 +7,12 @@ pub enum AppState {
     /// The main menu state.
     #[default]
     MainMenu,
+    /// The menu for changing each player's keys.
+    Controls,
     /// The in-game state.
     InGame,
+    /// The match is paused, with the pause menu shown over it.
+    Paused,
+    /// The results screen shown when a match is over.
+    GameOver,
 }
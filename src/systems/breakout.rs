@@ -0,0 +1,369 @@
+use bevy::{
+    prelude::{
+        info, warn, AssetServer, Assets, BuildChildren, Color, Commands, DespawnRecursiveExt,
+        DetectChanges, Entity, EventReader, NextState, Query, Res, ResMut, SpatialBundle,
+        Text2dBundle, Transform, Vec2, Visibility, With,
+    },
+    sprite::Sprite,
+    text::{Text, TextAlignment, TextStyle},
+};
+
+use crate::{
+    component::{
+        ball, bounding_box,
+        brick::{self, Brick},
+        collider::{self, Collider},
+        game::Game,
+        paddle::Side,
+        score::BreakoutText,
+        serve::{CountdownBundle, Serving},
+        velocity::{Friction, MaxSpeed},
+        wall, Bundle,
+    },
+    constants::{
+        BREAKOUT_BALL_POSITION, BREAKOUT_COUNTDOWN_POSITION, BREAKOUT_COURT_SIZE,
+        BREAKOUT_PADDLE_HEIGHT, BREAKOUT_STATUS_POSITION, BREAKOUT_WALL_THICKNESS, BRICK_POINTS,
+    },
+    events::score,
+    level::Level,
+    resources::{Bindings, Breakout, Levels, MatchStats, RallySpeed, Serve, Settings},
+    states::AppState,
+};
+
+/// Returns true if the game being played is Breakout rather than Pong.
+pub fn playing_breakout(breakout: Res<Breakout>) -> bool {
+    breakout.playing
+}
+
+/// Returns true if the game being played is Pong rather than Breakout.
+pub fn playing_pong(breakout: Res<Breakout>) -> bool {
+    !breakout.playing
+}
+
+/// Spawns everything needed to play a level of Breakout: the walls around
+/// the court, the level's bricks, and the player's paddle and the ball. The
+/// bottom of the court is the player's goal, and every ball that goes into it
+/// costs a life. A finished run makes way for a new one from the first level.
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_breakout(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut breakout: ResMut<Breakout>,
+    mut rally: ResMut<RallySpeed>,
+    mut serve: ResMut<Serve>,
+    mut stats: ResMut<MatchStats>,
+    bindings: Res<Bindings>,
+    settings: Res<Settings>,
+    levels: Res<Levels>,
+    level_assets: Res<Assets<Level>>,
+) {
+    let font = asset_server.load("fonts/NotoSansMono-Regular.ttf");
+    let text_style = TextStyle {
+        font,
+        font_size: 30.0,
+        color: Color::WHITE,
+    };
+
+    if breakout.is_over() {
+        breakout.start();
+        stats.reset();
+    }
+
+    // The levels should have loaded while the menu was up, but play on a
+    // plain wall of bricks rather than not at all if this one hasn't
+    let level = levels
+        .get(breakout.level, &level_assets)
+        .cloned()
+        .unwrap_or_else(|| {
+            warn!(
+                "Level {} isn't loaded, using the practice wall",
+                breakout.level + 1
+            );
+            Level::default()
+        });
+    info!("Playing level {}: {}", breakout.level + 1, level.name);
+
+    // The ball is always served down toward the player
+    rally.reset();
+    serve.start_match(Side::Bottom);
+
+    let court = BREAKOUT_COURT_SIZE;
+    let thickness = BREAKOUT_WALL_THICKNESS;
+    let paddle_y = BREAKOUT_PADDLE_HEIGHT - court.y / 2.0;
+    commands
+        .spawn((Game, SpatialBundle::default()))
+        .with_children(|parent| {
+            // The player's paddle, played with the left player's controls
+            parent.spawn(
+                Bundle::for_side(Side::Bottom)
+                    .with_seat(Side::Left)
+                    .with_bindings(&bindings)
+                    .with_friction(Friction(settings.paddle.friction))
+                    .with_max_speed(MaxSpeed(settings.paddle.speed))
+                    .with_position(Vec2::new(0.0, paddle_y)),
+            );
+            parent.spawn(
+                bounding_box::Bundle::default()
+                    .with_visibility(Visibility::Visible)
+                    .with_dimensions(court.x, 2.0 * BREAKOUT_PADDLE_HEIGHT)
+                    .with_position(Vec2::new(0.0, paddle_y))
+                    .on_side(Side::Bottom),
+            );
+
+            // Walls on three sides, and a goal along the bottom
+            for (position, size) in [
+                (
+                    Vec2::new(0.0, (court.y + thickness) / 2.0),
+                    Vec2::new(thickness.mul_add(2.0, court.x), thickness),
+                ),
+                (
+                    Vec2::new(-(court.x + thickness) / 2.0, 0.0),
+                    Vec2::new(thickness, court.y),
+                ),
+                (
+                    Vec2::new((court.x + thickness) / 2.0, 0.0),
+                    Vec2::new(thickness, court.y),
+                ),
+            ] {
+                parent.spawn(
+                    wall::Bundle::default()
+                        .with_size(size.x, size.y)
+                        .at(position)
+                        .visible(),
+                );
+            }
+            parent.spawn((
+                bounding_box::Bundle::default()
+                    .with_visibility(Visibility::Visible)
+                    .with_dimensions(court.x, thickness)
+                    .with_position(Vec2::new(0.0, -(court.y + thickness) / 2.0))
+                    .on_side(Side::Bottom),
+                Collider,
+                bounding_box::ScoreDetector,
+            ));
+
+            // bricks
+            for (position, hits) in level.bricks() {
+                parent.spawn(brick::Bundle::new(hits).at(position));
+            }
+
+            // ball
+            parent.spawn((
+                ball::Bundle::default().with_position(BREAKOUT_BALL_POSITION),
+                Serving::new(serve.first, serve.countdown),
+            ));
+
+            // Score, lives and level, and the level's name under the serve
+            // countdown
+            parent.spawn((
+                BreakoutText,
+                Text2dBundle {
+                    text: Text::from_section(status_label(&breakout), text_style.clone())
+                        .with_alignment(TextAlignment::Center),
+                    transform: Transform::from_translation(BREAKOUT_STATUS_POSITION.extend(0.0)),
+                    ..Default::default()
+                },
+            ));
+            parent.spawn(Text2dBundle {
+                text: Text::from_section(level.name, text_style.clone())
+                    .with_alignment(TextAlignment::Center),
+                transform: Transform::from_translation(
+                    (BREAKOUT_COUNTDOWN_POSITION - Vec2::new(0.0, 50.0)).extend(0.0),
+                ),
+                ..Default::default()
+            });
+            parent.spawn(
+                CountdownBundle::default()
+                    .with_style(text_style)
+                    .at(BREAKOUT_COUNTDOWN_POSITION),
+            );
+        });
+}
+
+/// The text showing how a Breakout run is going.
+fn status_label(breakout: &Breakout) -> String {
+    format!(
+        "Level {}   Score {}   Lives {}",
+        breakout.level + 1,
+        breakout.score,
+        breakout.lives
+    )
+}
+
+/// Scores a hit on every brick the ball bounced off, breaking the ones that
+/// have taken enough hits. Breaking the last brick clears the level; the next
+/// one is set up, or the run ends if it was the last.
+pub fn break_bricks(
+    mut commands: Commands,
+    mut ev_collision: EventReader<collider::Event>,
+    mut brick_q: Query<(Entity, &mut Brick, &mut Sprite)>,
+    levels: Res<Levels>,
+    mut breakout: ResMut<Breakout>,
+    mut state: ResMut<NextState<AppState>>,
+) {
+    let mut hit = false;
+    for ev in ev_collision.iter() {
+        let Some(Ok((entity, mut brick, mut sprite))) = ev.struck.map(|e| brick_q.get_mut(e))
+        else {
+            continue;
+        };
+        // Already broken, waiting to be despawned
+        if brick.hits == 0 {
+            continue;
+        }
+
+        hit = true;
+        breakout.score += BRICK_POINTS;
+        if brick.hit() {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            sprite.color = brick.color();
+        }
+    }
+
+    if !hit || brick_q.iter().any(|(_, brick, _)| brick.hits > 0) {
+        return;
+    }
+
+    breakout.clear_level(levels.len());
+    if breakout.is_over() {
+        info!("Every level cleared with {} points", breakout.score);
+        state.set(AppState::GameOver);
+    } else {
+        // Re-entering the state tears this level down and sets up the next
+        info!("Level cleared, on to level {}", breakout.level + 1);
+        state.set(AppState::InGame);
+    }
+}
+
+/// Takes a life away every time the ball gets past the player, ending the run
+/// when they have none left.
+pub fn lose_life(
+    mut ev_score: EventReader<score::Event>,
+    mut breakout: ResMut<Breakout>,
+    mut state: ResMut<NextState<AppState>>,
+) {
+    if ev_score.iter().next().is_none() {
+        return;
+    }
+    ev_score.clear();
+
+    breakout.lose_life();
+    info!("Ball lost, {} lives left", breakout.lives);
+    if breakout.is_over() {
+        state.set(AppState::GameOver);
+    }
+}
+
+/// Keeps the score, lives and level shown during a game of Breakout up to
+/// date.
+pub fn show_breakout_status(
+    breakout: Res<Breakout>,
+    mut text_q: Query<&mut Text, With<BreakoutText>>,
+) {
+    if !breakout.is_changed() {
+        return;
+    }
+    for mut text in &mut text_q {
+        text.sections[0].value = status_label(&breakout);
+    }
+}
+
+/// Saves the high score if the last run beat it.
+pub fn save_high_score(breakout: Res<Breakout>) {
+    if breakout.new_best {
+        info!("Saving high score");
+        breakout.save();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::{App, Handle, IntoSystemConfigs, MinimalPlugins, State};
+
+    use super::*;
+    use crate::{
+        constants::BREAKOUT_LIVES, plugins::shake, resources::Deflection, systems::collide_ball,
+        tests::helpers::steady_rally,
+    };
+
+    #[test]
+    fn bricks_break_and_score_until_the_level_is_cleared() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_state::<AppState>()
+            .add_event::<collider::Event>()
+            .add_event::<shake::Event>()
+            .init_resource::<Deflection>()
+            .insert_resource(steady_rally())
+            .insert_resource(Levels {
+                handles: vec![Handle::default(); 2],
+            })
+            .init_resource::<Breakout>()
+            .add_systems((collide_ball, break_bricks).chain());
+        app.world.resource_mut::<Breakout>().start();
+
+        // The ball bounces back and forth between a wall and a brick that
+        // takes two hits
+        app.world.spawn(
+            wall::Bundle::default()
+                .with_size(20.0, 200.0)
+                .at(Vec2::new(-100.0, 0.0)),
+        );
+        let brick = app
+            .world
+            .spawn(brick::Bundle::new(2).at(Vec2::new(100.0, 0.0)))
+            .id();
+        app.world.spawn(
+            ball::Bundle::default()
+                .with_position(Vec2::new(60.0, 0.0))
+                .with_velocity(Vec2::new(600.0, 0.0)),
+        );
+
+        app.update();
+        assert_eq!(app.world.get::<Brick>(brick), Some(&Brick { hits: 1 }));
+        assert_eq!(app.world.resource::<Breakout>().score, BRICK_POINTS);
+
+        for _ in 0..120 {
+            app.update();
+        }
+        assert!(app.world.get_entity(brick).is_none());
+        let breakout = *app.world.resource::<Breakout>();
+        assert_eq!(breakout.score, 2 * BRICK_POINTS);
+        assert_eq!(breakout.level, 1);
+        assert!(!breakout.is_over());
+        assert_eq!(app.world.resource::<State<AppState>>().0, AppState::InGame);
+    }
+
+    #[test]
+    fn missing_the_ball_costs_a_life_until_the_run_is_over() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_state::<AppState>()
+            .add_event::<score::Event>()
+            .init_resource::<Breakout>()
+            .add_system(lose_life);
+        app.world.resource_mut::<Breakout>().start();
+
+        app.world
+            .send_event(score::Event::new(Side::Top).conceded_by(Side::Bottom));
+        app.update();
+        assert_eq!(app.world.resource::<Breakout>().lives, BREAKOUT_LIVES - 1);
+        assert_eq!(
+            app.world.resource::<State<AppState>>().0,
+            AppState::MainMenu
+        );
+
+        for _ in 1..BREAKOUT_LIVES {
+            app.world
+                .send_event(score::Event::new(Side::Top).conceded_by(Side::Bottom));
+            app.update();
+        }
+        app.update();
+        assert!(app.world.resource::<Breakout>().is_over());
+        assert_eq!(
+            app.world.resource::<State<AppState>>().0,
+            AppState::GameOver
+        );
+    }
+}
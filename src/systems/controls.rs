@@ -0,0 +1,730 @@
+use bevy::{
+    input::{
+        gamepad::GamepadConnectionEvent,
+        touch::{Touch, Touches},
+    },
+    prelude::{
+        info, Axis, Camera2d, EventReader, GamepadAxis, GamepadAxisType, GamepadButton,
+        GamepadButtonType, Input, KeyCode, MouseButton, OrthographicProjection, Query, Res, ResMut,
+        Transform, Vec2, With, Without,
+    },
+    utils::HashMap,
+    window::{PrimaryWindow, Window},
+};
+
+use crate::{
+    component::{
+        bounding_box::{BoundingBox, ScoreDetector},
+        controls::{Action, ActionEvent, ActionState, Controller},
+        paddle::{Player, Role, Side},
+    },
+    plugins::window_scaling_2d::{touch_to_window, window_to_logical},
+    resources::{Bindings, GamepadAssignment, StickDeadZone},
+};
+
+/// Which gamepad buttons trigger which actions.
+const GAMEPAD_BUTTONS: [(GamepadButtonType, Action); 6] = [
+    (GamepadButtonType::DPadUp, Action::MoveUp),
+    (GamepadButtonType::DPadDown, Action::MoveDown),
+    (GamepadButtonType::DPadLeft, Action::MoveLeft),
+    (GamepadButtonType::DPadRight, Action::MoveRight),
+    (GamepadButtonType::South, Action::Serve),
+    (GamepadButtonType::Start, Action::Pause),
+];
+
+/// Hands gamepads to the players as they are connected, and takes them away
+/// again when they are disconnected.
+pub fn assign_gamepads(
+    mut ev_connection: EventReader<GamepadConnectionEvent>,
+    mut assignment: ResMut<GamepadAssignment>,
+) {
+    for ev in ev_connection.iter() {
+        if ev.connected() {
+            match assignment.connect(ev.gamepad) {
+                Some(side) => info!("{:?} is playing {:?}", ev.gamepad, side),
+                None => info!("{:?} connected, but both sides have a gamepad", ev.gamepad),
+            }
+        } else if let Some(side) = assignment.disconnect(ev.gamepad) {
+            info!("{:?} lost its gamepad, falling back to the keyboard", side);
+        }
+    }
+}
+
+/// Switches each player's paddle to the gamepad they've been handed, or back
+/// to their keyboard layout if their gamepad has gone away. Paddles that
+/// aren't controlled by a local player are left alone.
+pub fn use_assigned_gamepads(
+    assignment: Res<GamepadAssignment>,
+    bindings: Res<Bindings>,
+    mut paddle_q: Query<(&Player, &mut Controller)>,
+) {
+    for (player, mut controller) in &mut paddle_q {
+        let wanted = match (assignment.get(player.controls()), *controller) {
+            (Some(gamepad), Controller::Keyboard(_) | Controller::Gamepad(_)) => {
+                Controller::Gamepad(gamepad)
+            }
+            (None, Controller::Gamepad(_)) => Controller::Keyboard(bindings.get(player.controls())),
+            _ => continue,
+        };
+        if *controller != wanted {
+            *controller = wanted;
+        }
+    }
+}
+
+/// How far a pointer-controlled paddle keeps from the edges of its zone, so
+/// that it never presses up against them and gets stuck.
+const ZONE_MARGIN: f32 = 1.0;
+
+/// The zones paddles are allowed to move in.
+type ZoneQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Transform, &'static BoundingBox),
+    (Without<Player>, Without<ScoreDetector>),
+>;
+
+/// The window and the scale of the camera looking at it, for turning
+/// positions in the window into game coordinates. Returns `None` if there's
+/// no window to look at.
+fn pointer_view<'a>(
+    windows: &'a Query<&Window, With<PrimaryWindow>>,
+    projections: &Query<&OrthographicProjection, With<Camera2d>>,
+) -> Option<(&'a Window, f32)> {
+    let window = windows.get_single().ok()?;
+    let scale = projections.get_single().map_or(1.0, |proj| proj.scale);
+    Some((window, scale))
+}
+
+/// Where the mouse cursor is, in game coordinates.
+fn cursor_position(view: Option<(&Window, f32)>) -> Option<Vec2> {
+    let (window, scale) = view?;
+    let cursor = window.cursor_position()?;
+    Some(window_to_logical(window, cursor, scale))
+}
+
+/// Where a finger is, in game coordinates.
+fn touch_position(view: Option<(&Window, f32)>, touch: &Touch) -> Option<Vec2> {
+    let (window, scale) = view?;
+    let position = touch_to_window(window, touch.position());
+    Some(window_to_logical(window, position, scale))
+}
+
+/// The goals still in play, for telling whose end of the court a position is
+/// at.
+type GoalQuery<'w, 's> =
+    Query<'w, 's, (&'static Transform, &'static BoundingBox), With<ScoreDetector>>;
+
+/// The side whose goal is nearest a position in game coordinates, out of the
+/// sides the match has. Returns `None` if there are no goals.
+fn nearest_goal(position: Vec2, goal_q: &GoalQuery) -> Option<Side> {
+    goal_q
+        .iter()
+        .map(|(goal, bb)| {
+            let offset = (position - goal.translation.truncate()).abs();
+            let gap = (offset - goal.scale.truncate() / 2.0).max(Vec2::ZERO);
+            (bb.side, gap.length())
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(side, _)| side)
+}
+
+/// Keeps a target inside the zone `player`'s paddle is allowed to move in.
+fn clamp_to_zone(target: Vec2, paddle: &Transform, player: &Player, zone_q: &ZoneQuery) -> Vec2 {
+    let Some((zone, _)) = zone_q
+        .iter()
+        .find(|(_, bb)| bb.side == player.side && bb.role == player.role)
+    else {
+        return target;
+    };
+
+    let room = ((zone.scale - paddle.scale).truncate() / 2.0 - ZONE_MARGIN).max(Vec2::ZERO);
+    let centre = zone.translation.truncate();
+    target.clamp(centre - room, centre + room)
+}
+
+/// Hands a paddle to the mouse when someone clicks nearer its goal than any
+/// other, or to a finger when someone touches there. Only one paddle follows
+/// the mouse at a time, and only a side's defender is handed a pointer, so
+/// one click doesn't take over both paddles in doubles. Pressing a paddle's
+/// keys hands it back to the keyboard.
+#[allow(clippy::too_many_arguments)]
+pub fn use_pointer_controllers(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    projections: Query<&OrthographicProjection, With<Camera2d>>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    touches: Res<Touches>,
+    bindings: Res<Bindings>,
+    mut paddle_q: Query<(&Player, &mut Controller)>,
+    goal_q: GoalQuery,
+) {
+    let view = pointer_view(&windows, &projections);
+    let clicked = buttons
+        .just_pressed(MouseButton::Left)
+        .then(|| cursor_position(view))
+        .flatten()
+        .and_then(|position| nearest_goal(position, &goal_q));
+    let touched: Vec<Side> = touches
+        .iter_just_pressed()
+        .filter_map(|touch| nearest_goal(touch_position(view, touch)?, &goal_q))
+        .collect();
+
+    for (player, mut controller) in &mut paddle_q {
+        let side = player.side;
+        let defender = player.role == Role::Defender;
+        let keyboard = bindings.get(player.controls());
+        let wanted = match *controller {
+            Controller::Keyboard(_) | Controller::Gamepad(_) | Controller::Mouse
+                if defender && touched.contains(&side) =>
+            {
+                Controller::Touch
+            }
+            Controller::Keyboard(_) | Controller::Gamepad(_)
+                if defender && clicked == Some(side) =>
+            {
+                Controller::Mouse
+            }
+            Controller::Mouse if clicked.is_some_and(|clicked| clicked != side) => {
+                Controller::Keyboard(keyboard)
+            }
+            Controller::Mouse | Controller::Touch
+                if keys.get_pressed().any(|k| keyboard.action(k).is_some()) =>
+            {
+                Controller::Keyboard(keyboard)
+            }
+            _ => continue,
+        };
+        if *controller != wanted {
+            info!("{:?} is now played with {:?}", side, wanted);
+            *controller = wanted;
+        }
+    }
+}
+
+/// Points every mouse-controlled paddle at the cursor, as far as its zone
+/// allows. The left button serves and the right button pauses.
+pub fn mouse_actions(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    projections: Query<&OrthographicProjection, With<Camera2d>>,
+    buttons: Res<Input<MouseButton>>,
+    mut paddle_q: Query<(&Player, &Transform, &Controller, &mut ActionState)>,
+    zone_q: ZoneQuery,
+) {
+    let cursor = cursor_position(pointer_view(&windows, &projections));
+
+    for (player, tf, controller, mut actions) in &mut paddle_q {
+        if *controller != Controller::Mouse {
+            continue;
+        }
+
+        actions.begin_frame();
+        actions.clear();
+        actions.set_target(cursor.map(|cursor| clamp_to_zone(cursor, tf, player, &zone_q)));
+        if buttons.pressed(MouseButton::Left) {
+            actions.press(Action::Serve);
+        }
+        if buttons.pressed(MouseButton::Right) {
+            actions.press(Action::Pause);
+        }
+    }
+}
+
+/// Points every touch-controlled paddle at the finger nearest its goal, as far
+/// as its zone allows, so players can share one screen. If more than one
+/// finger is nearest the same goal, the first one down wins.
+pub fn touch_actions(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    projections: Query<&OrthographicProjection, With<Camera2d>>,
+    touches: Res<Touches>,
+    mut paddle_q: Query<(&Player, &Transform, &Controller, &mut ActionState)>,
+    zone_q: ZoneQuery,
+    goal_q: GoalQuery,
+) {
+    let view = pointer_view(&windows, &projections);
+    let mut fingers: Vec<(u64, Vec2)> = touches
+        .iter()
+        .filter_map(|touch| Some((touch.id(), touch_position(view, touch)?)))
+        .collect();
+    fingers.sort_by_key(|(id, _)| *id);
+
+    for (player, tf, controller, mut actions) in &mut paddle_q {
+        if *controller != Controller::Touch {
+            continue;
+        }
+
+        let finger = fingers
+            .iter()
+            .find(|(_, position)| nearest_goal(*position, &goal_q) == Some(player.side));
+        actions.begin_frame();
+        actions.clear();
+        actions
+            .set_target(finger.map(|(_, position)| clamp_to_zone(*position, tf, player, &zone_q)));
+    }
+}
+
+/// Reads the keyboard into the actions of every paddle controlled by a
+/// keyboard layout.
+pub fn keyboard_actions(
+    keys: Res<Input<KeyCode>>,
+    mut paddle_q: Query<(&Controller, &mut ActionState)>,
+) {
+    for (controller, mut actions) in &mut paddle_q {
+        let Controller::Keyboard(layout) = controller else {
+            continue;
+        };
+
+        actions.begin_frame();
+        actions.clear();
+        keys.get_pressed()
+            .filter_map(|k| layout.action(k))
+            .for_each(|action| actions.press(action));
+    }
+}
+
+/// Reads gamepads into the actions of every paddle controlled by one. The
+/// left stick is read through the dead zone, and the d-pad works like the
+/// keyboard's movement keys.
+pub fn gamepad_actions(
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    dead_zone: Res<StickDeadZone>,
+    mut paddle_q: Query<(&Controller, &mut ActionState)>,
+) {
+    for (controller, mut actions) in &mut paddle_q {
+        let Controller::Gamepad(gamepad) = *controller else {
+            continue;
+        };
+
+        actions.begin_frame();
+        actions.clear();
+        for (button_type, action) in GAMEPAD_BUTTONS {
+            if buttons.pressed(GamepadButton::new(gamepad, button_type)) {
+                actions.press(action);
+            }
+        }
+
+        let axis = |axis_type| {
+            axes.get(GamepadAxis::new(gamepad, axis_type))
+                .unwrap_or_default()
+        };
+        actions.set_axis(dead_zone.apply(Vec2::new(
+            axis(GamepadAxisType::LeftStickX),
+            axis(GamepadAxisType::LeftStickY),
+        )));
+    }
+}
+
+/// Applies `ActionEvent`s to the paddles controlled over the network or by a
+/// replay. A paddle keeps doing what it was last told until the next event
+/// for it arrives.
+pub fn remote_actions(
+    mut ev_actions: EventReader<ActionEvent>,
+    mut paddle_q: Query<(&Player, &Controller, &mut ActionState)>,
+) {
+    // Only the latest event for each set of controls matters
+    let latest: HashMap<_, _> = ev_actions.iter().map(|ev| (ev.side, &ev.actions)).collect();
+
+    for (player, controller, mut actions) in &mut paddle_q {
+        if !controller.is_remote() {
+            continue;
+        }
+
+        match latest.get(&player.controls()) {
+            Some(remote) => actions.copy_from(remote),
+            None => actions.begin_frame(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{
+        input::{
+            gamepad::{
+                GamepadAxisChangedEvent, GamepadConnection, GamepadConnectionEvent, GamepadEvent,
+                GamepadInfo,
+            },
+            mouse::MouseButtonInput,
+            touch::{TouchInput, TouchPhase},
+            ButtonState, InputPlugin, InputSystem,
+        },
+        prelude::{App, Entity, Gamepad, GamepadAxisType, IntoSystemConfigs, MinimalPlugins},
+        window::WindowResolution,
+    };
+
+    use super::*;
+    use crate::{
+        component::{bounding_box, controls::Keyboard, velocity::Velocity, Bundle},
+        constants::PADDLE_SPEED_MULTIPLIER,
+        systems::paddle_input,
+        tests::helpers::{default_setup_graphics, Test},
+    };
+
+    #[test]
+    fn keys_move_their_paddle_through_actions() {
+        Test {
+            setup: |app| {
+                app.init_resource::<Input<KeyCode>>()
+                    .add_systems((keyboard_actions, paddle_input).chain());
+                app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::W);
+                app.world.spawn(Bundle::right_player());
+                app.world.spawn(Bundle::left_player()).id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 1,
+            check: |app, left| {
+                let actions = app.world.get::<ActionState>(left).unwrap();
+                assert!(actions.just_pressed(Action::MoveUp));
+
+                // Only the paddle bound to W moves
+                for (player, vel) in app
+                    .world
+                    .iter_entities()
+                    .filter_map(|e| Some((e.get::<Player>()?, e.get::<Velocity>()?)))
+                {
+                    match player.side {
+                        Side::Left => assert_eq!(**vel, Vec2::Y * PADDLE_SPEED_MULTIPLIER),
+                        _ => assert_eq!(**vel, Vec2::ZERO),
+                    }
+                }
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn remote_actions_control_their_paddle() {
+        Test {
+            setup: |app| {
+                app.add_event::<ActionEvent>()
+                    .add_systems((remote_actions, paddle_input).chain());
+                let mut actions = ActionState::default();
+                actions.press(Action::MoveDown);
+                app.world.send_event(ActionEvent {
+                    side: Side::Left,
+                    actions,
+                });
+                app.world
+                    .spawn(Bundle::new(Controller::Network, Side::Left))
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 1,
+            check: |app, paddle| {
+                let vel = app.world.get::<Velocity>(paddle).unwrap();
+                assert_eq!(**vel, Vec2::NEG_Y * PADDLE_SPEED_MULTIPLIER);
+            },
+        }
+        .run();
+    }
+
+    /// An app that reads synthetic gamepad events into a left paddle, the same
+    /// way the game does.
+    fn gamepad_app() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(InputPlugin)
+            .init_resource::<Bindings>()
+            .init_resource::<GamepadAssignment>()
+            .init_resource::<StickDeadZone>()
+            .add_systems(
+                (
+                    assign_gamepads,
+                    use_assigned_gamepads,
+                    keyboard_actions,
+                    gamepad_actions,
+                    paddle_input,
+                )
+                    .chain()
+                    .after(InputSystem),
+            );
+        let paddle = app.world.spawn(Bundle::left_player()).id();
+        (app, paddle)
+    }
+
+    fn connection(gamepad: Gamepad, connected: bool) -> GamepadEvent {
+        let connection = if connected {
+            GamepadConnection::Connected(GamepadInfo {
+                name: "Test pad".to_string(),
+            })
+        } else {
+            GamepadConnection::Disconnected
+        };
+        GamepadConnectionEvent::new(gamepad, connection).into()
+    }
+
+    #[test]
+    fn connected_gamepad_moves_its_paddle() {
+        let (mut app, paddle) = gamepad_app();
+        let gamepad = Gamepad::new(0);
+        app.world.send_event(connection(gamepad, true));
+        app.world.send_event::<GamepadEvent>(
+            GamepadAxisChangedEvent::new(gamepad, GamepadAxisType::LeftStickY, 0.5).into(),
+        );
+        app.update();
+
+        assert_eq!(
+            *app.world.get::<Controller>(paddle).unwrap(),
+            Controller::Gamepad(gamepad)
+        );
+
+        // Half a push, less the dead zone, stretched back out to full range
+        let expected = StickDeadZone::default().apply(Vec2::new(0.0, 0.5)).y;
+        let vel = app.world.get::<Velocity>(paddle).unwrap();
+        assert!((vel.y - expected * PADDLE_SPEED_MULTIPLIER).abs() < 1e-4);
+        assert!(expected < 0.5);
+
+        // A nudge inside the dead zone does nothing
+        app.world.send_event::<GamepadEvent>(
+            GamepadAxisChangedEvent::new(gamepad, GamepadAxisType::LeftStickY, 0.1).into(),
+        );
+        app.update();
+        let actions = app.world.get::<ActionState>(paddle).unwrap();
+        assert_eq!(actions.movement(), Vec2::ZERO);
+    }
+
+    #[test]
+    fn disconnected_gamepad_falls_back_to_the_keyboard() {
+        let (mut app, paddle) = gamepad_app();
+        let gamepad = Gamepad::new(0);
+        app.world.send_event(connection(gamepad, true));
+        app.update();
+        app.world.send_event(connection(gamepad, false));
+        app.update();
+
+        assert_eq!(
+            *app.world.get::<Controller>(paddle).unwrap(),
+            Controller::Keyboard(Keyboard::for_side(Side::Left))
+        );
+        assert_eq!(
+            *app.world.resource::<GamepadAssignment>(),
+            Default::default()
+        );
+
+        // The keyboard works again straight away
+        app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::S);
+        app.update();
+        let vel = app.world.get::<Velocity>(paddle).unwrap();
+        assert_eq!(**vel, Vec2::NEG_Y * PADDLE_SPEED_MULTIPLIER);
+    }
+
+    /// An app with a 400x300 window, seen through a camera zoomed out to twice
+    /// that size, that reads synthetic mouse and touch events into a pair of
+    /// paddles, each with a goal behind it, the same way the game does.
+    fn pointer_app() -> (App, Entity, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(InputPlugin)
+            .init_resource::<Bindings>()
+            .add_systems(
+                (
+                    use_pointer_controllers,
+                    keyboard_actions,
+                    mouse_actions,
+                    touch_actions,
+                    paddle_input,
+                )
+                    .chain()
+                    .after(InputSystem),
+            );
+        app.world.spawn((
+            Window {
+                resolution: WindowResolution::new(400.0, 300.0),
+                ..Default::default()
+            },
+            PrimaryWindow,
+        ));
+        app.world.spawn((
+            Camera2d::default(),
+            OrthographicProjection {
+                scale: 2.0,
+                ..Default::default()
+            },
+        ));
+        for (side, x) in [(Side::Left, -125.0), (Side::Right, 125.0)] {
+            app.world.spawn(
+                bounding_box::Bundle::default()
+                    .with_dimensions(250.0, 500.0)
+                    .with_position(Vec2::new(x, 0.0))
+                    .on_side(side),
+            );
+            app.world.spawn((
+                bounding_box::Bundle::default()
+                    .with_dimensions(25.0, 500.0)
+                    .with_position(Vec2::new(x * 2.1, 0.0))
+                    .on_side(side),
+                bounding_box::ScoreDetector,
+            ));
+        }
+        let left = app.world.spawn(Bundle::left_player()).id();
+        let right = app.world.spawn(Bundle::right_player()).id();
+        (app, left, right)
+    }
+
+    #[test]
+    fn clicked_paddle_follows_the_mouse_inside_its_zone() {
+        let (mut app, left, right) = pointer_app();
+        app.world
+            .query::<&mut Window>()
+            .single_mut(&mut app.world)
+            .set_cursor_position(Some(Vec2::new(50.0, 250.0)));
+        app.world.send_event(MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+        });
+        app.update();
+
+        assert_eq!(
+            *app.world.get::<Controller>(left).unwrap(),
+            Controller::Mouse
+        );
+        assert_ne!(
+            *app.world.get::<Controller>(right).unwrap(),
+            Controller::Mouse
+        );
+
+        // The cursor is at (-300, 200) in the game, past the top left corner
+        // of the left zone, so the paddle heads for the corner instead
+        let actions = app.world.get::<ActionState>(left).unwrap();
+        assert_eq!(actions.target(), Some(Vec2::new(-239.0, 199.0)));
+        assert!(actions.pressed(Action::Serve));
+
+        // It can't go any faster than the keys would move it
+        let vel = app.world.get::<Velocity>(left).unwrap();
+        assert!((vel.length() - PADDLE_SPEED_MULTIPLIER).abs() < 1e-3);
+        assert!(vel.x < 0.0 && vel.y > 0.0);
+    }
+
+    #[test]
+    fn two_fingers_each_control_their_own_half() {
+        let (mut app, left, right) = pointer_app();
+        // Touches are in logical pixels, measured from the top left
+        for (id, position) in [(1, Vec2::new(50.0, 100.0)), (2, Vec2::new(350.0, 150.0))] {
+            app.world.send_event(TouchInput {
+                phase: TouchPhase::Started,
+                position,
+                force: None,
+                id,
+            });
+        }
+        app.update();
+
+        for (paddle, target) in [
+            (left, Vec2::new(-239.0, 100.0)),
+            (right, Vec2::new(239.0, 0.0)),
+        ] {
+            assert_eq!(
+                *app.world.get::<Controller>(paddle).unwrap(),
+                Controller::Touch
+            );
+            let actions = app.world.get::<ActionState>(paddle).unwrap();
+            assert_eq!(actions.target(), Some(target));
+        }
+
+        // Lifting a finger leaves that paddle where it is
+        app.world.send_event(TouchInput {
+            phase: TouchPhase::Ended,
+            position: Vec2::new(50.0, 100.0),
+            force: None,
+            id: 1,
+        });
+        app.update();
+        app.update();
+        let actions = app.world.get::<ActionState>(left).unwrap();
+        assert_eq!(actions.target(), None);
+        let actions = app.world.get::<ActionState>(right).unwrap();
+        assert!(actions.target().is_some());
+    }
+
+    #[test]
+    fn touches_are_not_scaled_on_high_dpi_screens() {
+        let (mut app, left, _) = pointer_app();
+        let mut window_q = app.world.query::<&mut Window>();
+        window_q
+            .single_mut(&mut app.world)
+            .resolution
+            .set_scale_factor_override(Some(2.0));
+
+        // The same touch lands in the same place as at a scale factor of 1
+        app.world.send_event(TouchInput {
+            phase: TouchPhase::Started,
+            position: Vec2::new(50.0, 100.0),
+            force: None,
+            id: 1,
+        });
+        app.update();
+
+        let actions = app.world.get::<ActionState>(left).unwrap();
+        assert_eq!(actions.target(), Some(Vec2::new(-239.0, 100.0)));
+    }
+
+    #[test]
+    fn clicks_go_to_the_paddle_whose_goal_is_nearest() {
+        let (mut app, left, right) = pointer_app();
+        app.world.spawn((
+            bounding_box::Bundle::default()
+                .with_dimensions(500.0, 25.0)
+                .with_position(Vec2::new(0.0, 262.5))
+                .on_side(Side::Top),
+            bounding_box::ScoreDetector,
+        ));
+        let top = app.world.spawn(Bundle::for_side(Side::Top)).id();
+
+        // (0, 260) in the game, right by the top goal
+        app.world
+            .query::<&mut Window>()
+            .single_mut(&mut app.world)
+            .set_cursor_position(Some(Vec2::new(200.0, 280.0)));
+        app.world.send_event(MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+        });
+        app.update();
+
+        assert_eq!(
+            *app.world.get::<Controller>(top).unwrap(),
+            Controller::Mouse
+        );
+        for paddle in [left, right] {
+            assert_ne!(
+                *app.world.get::<Controller>(paddle).unwrap(),
+                Controller::Mouse
+            );
+        }
+    }
+
+    #[test]
+    fn only_the_defender_takes_the_pointer_in_doubles() {
+        let (mut app, left, _) = pointer_app();
+        let attacker = app
+            .world
+            .spawn(Bundle::left_player().with_role(Role::Attacker))
+            .id();
+
+        app.world
+            .query::<&mut Window>()
+            .single_mut(&mut app.world)
+            .set_cursor_position(Some(Vec2::new(50.0, 150.0)));
+        app.world.send_event(MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+        });
+        app.world.send_event(TouchInput {
+            phase: TouchPhase::Started,
+            position: Vec2::new(50.0, 150.0),
+            force: None,
+            id: 1,
+        });
+        app.update();
+
+        assert_eq!(
+            *app.world.get::<Controller>(left).unwrap(),
+            Controller::Touch
+        );
+        assert!(matches!(
+            *app.world.get::<Controller>(attacker).unwrap(),
+            Controller::Keyboard(_)
+        ));
+    }
+}
@@ -0,0 +1,311 @@
+use bevy::{
+    prelude::{
+        debug, info, AssetServer, BuildChildren, Color, Commands, DespawnRecursiveExt, Entity,
+        Input, KeyCode, NextState, Query, Res, ResMut, SpatialBundle, Text2dBundle, Transform,
+        With, Without,
+    },
+    text::{Text, TextAlignment, TextStyle},
+};
+
+use crate::{
+    component::{
+        controls::Action,
+        controls_menu::{BindingText, ControlsMenu, ControlsMessage},
+        paddle::Side,
+    },
+    resources::Bindings,
+    states::AppState,
+};
+
+use super::pause::{QUIT_KEY, RESTART_KEY};
+
+/// Where each side's bindings are shown across the screen.
+const COLUMNS: [(Side, f32); 4] = [
+    (Side::Left, -450.0),
+    (Side::Right, -150.0),
+    (Side::Top, 150.0),
+    (Side::Bottom, 450.0),
+];
+
+/// The text shown for a binding, with the selected one marked.
+fn binding_label(menu: &ControlsMenu, bindings: &Bindings, side: Side, action: Action) -> String {
+    let selected = menu.side == side && menu.action() == action;
+    let key = if selected && menu.listening {
+        "...".to_string()
+    } else {
+        format!("{:?}", bindings.get(side).key(action))
+    };
+
+    if selected {
+        format!("> {}: {} <", action.name(), key)
+    } else {
+        format!("{}: {}", action.name(), key)
+    }
+}
+
+/// Shows every side's key bindings, with the first one selected.
+pub fn setup_controls_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    bindings: Res<Bindings>,
+) {
+    info!("Now in controls menu");
+
+    let font = asset_server.load("fonts/NotoSansMono-Regular.ttf");
+    let title_style = TextStyle {
+        font: font.clone(),
+        font_size: 60.0,
+        color: Color::WHITE,
+    };
+    let prompt_style = TextStyle {
+        font: font.clone(),
+        font_size: 30.0,
+        color: Color::WHITE,
+    };
+    // Smaller, so that every side's column fits across the screen
+    let binding_style = TextStyle {
+        font,
+        font_size: 20.0,
+        color: Color::WHITE,
+    };
+
+    let menu = ControlsMenu::default();
+    let labels: Vec<_> = COLUMNS
+        .into_iter()
+        .flat_map(|(side, x)| {
+            Action::ALL
+                .into_iter()
+                .enumerate()
+                .map(move |(row, action)| {
+                    let y = (row as f32).mul_add(-40.0, 100.0);
+                    (side, action, Transform::from_xyz(x, y, 0.0))
+                })
+        })
+        .map(|(side, action, transform)| {
+            let label = binding_label(&menu, &bindings, side, action);
+            (BindingText { side, action }, label, transform)
+        })
+        .collect();
+
+    commands
+        .spawn((menu, SpatialBundle::default()))
+        .with_children(|parent| {
+            parent.spawn(Text2dBundle {
+                text: Text::from_section("C O N T R O L S", title_style)
+                    .with_alignment(TextAlignment::Center),
+                transform: Transform::from_xyz(0.0, 230.0, 0.0),
+                ..Default::default()
+            });
+
+            for (side, x) in COLUMNS {
+                parent.spawn(Text2dBundle {
+                    text: Text::from_section(format!("{side:?}"), prompt_style.clone())
+                        .with_alignment(TextAlignment::Center),
+                    transform: Transform::from_xyz(x, 150.0, 0.0),
+                    ..Default::default()
+                });
+            }
+
+            for (marker, label, transform) in labels {
+                parent.spawn((
+                    marker,
+                    Text2dBundle {
+                        text: Text::from_section(label, binding_style.clone())
+                            .with_alignment(TextAlignment::Center),
+                        transform,
+                        ..Default::default()
+                    },
+                ));
+            }
+
+            parent.spawn((
+                ControlsMessage,
+                Text2dBundle {
+                    text: Text::from_section("", prompt_style.clone())
+                        .with_alignment(TextAlignment::Center),
+                    transform: Transform::from_xyz(0.0, -180.0, 0.0),
+                    ..Default::default()
+                },
+            ));
+
+            parent.spawn(Text2dBundle {
+                text: Text::from_section("Enter - Rebind\nEsc - Back", prompt_style)
+                    .with_alignment(TextAlignment::Center),
+                transform: Transform::from_xyz(0.0, -250.0, 0.0),
+                ..Default::default()
+            });
+        });
+}
+
+/// Moves around the controls menu, and binds the next key pressed to the
+/// selected action once the player has asked to rebind it. A key that is
+/// already in use isn't bound, and the player is told what it's used for.
+/// Escape cancels a rebind rather than being bound, and the pause menu's own
+/// keys can't be bound to pause.
+pub fn read_controls_menu_keypresses(
+    keys: Res<Input<KeyCode>>,
+    mut menu_q: Query<&mut ControlsMenu>,
+    mut bindings: ResMut<Bindings>,
+    mut state: ResMut<NextState<AppState>>,
+) {
+    let Ok(mut menu) = menu_q.get_single_mut() else {
+        return;
+    };
+    let Some(&key) = keys.get_just_pressed().next() else {
+        return;
+    };
+    debug!("Key pressed: {:?}", key);
+
+    if menu.listening {
+        let (side, action) = (menu.side, menu.action());
+        menu.listening = false;
+        if key == KeyCode::Escape {
+            menu.message = "Rebind cancelled".to_string();
+            return;
+        }
+        if action == Action::Pause && [RESTART_KEY, QUIT_KEY].contains(&key) {
+            menu.message = format!("{key:?} is used by the pause menu");
+            return;
+        }
+        menu.message = match bindings.bind(side, action, key) {
+            Ok(()) => format!("{:?} {} is now {:?}", side, action.name(), key),
+            Err(conflict) => format!(
+                "{:?} is already {:?} {}",
+                key,
+                conflict.side,
+                conflict.action.name()
+            ),
+        };
+        return;
+    }
+
+    match key {
+        KeyCode::Up => menu.move_selection(-1),
+        KeyCode::Down => menu.move_selection(1),
+        KeyCode::Left => menu.move_side(-1),
+        KeyCode::Right => menu.move_side(1),
+        KeyCode::Return => {
+            menu.listening = true;
+            menu.message = "Press a key, or Esc to cancel".to_string();
+        }
+        KeyCode::Escape => state.set(AppState::MainMenu),
+        _ => {}
+    }
+}
+
+/// Keeps the bindings and the message shown in the controls menu up to date.
+pub fn update_controls_menu_text(
+    bindings: Res<Bindings>,
+    menu_q: Query<&ControlsMenu>,
+    mut binding_q: Query<(&mut Text, &BindingText), Without<ControlsMessage>>,
+    mut message_q: Query<&mut Text, With<ControlsMessage>>,
+) {
+    let Ok(menu) = menu_q.get_single() else {
+        return;
+    };
+
+    for (mut text, binding) in &mut binding_q {
+        let label = binding_label(menu, &bindings, binding.side, binding.action);
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+    }
+    for mut text in &mut message_q {
+        if text.sections[0].value != menu.message {
+            text.sections[0].value.clone_from(&menu.message);
+        }
+    }
+}
+
+/// Saves the bindings to the player's config file.
+pub fn save_bindings(bindings: Res<Bindings>) {
+    info!("Saving controls");
+    bindings.save();
+}
+
+/// Removes the controls menu.
+pub fn teardown_controls_menu(query: Query<Entity, With<ControlsMenu>>, mut commands: Commands) {
+    info!("Now leaving controls menu");
+
+    let ent = query.single();
+    commands.entity(ent).despawn_recursive();
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{
+        input::{keyboard::KeyboardInput, ButtonState, InputPlugin, InputSystem},
+        prelude::{App, IntoSystemConfig, MinimalPlugins},
+    };
+
+    use super::*;
+
+    #[test]
+    fn rebinding_a_key_checks_for_conflicts() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(InputPlugin)
+            .add_state::<AppState>()
+            .init_resource::<Bindings>()
+            .add_system(read_controls_menu_keypresses.after(InputSystem));
+        let menu = app.world.spawn(ControlsMenu::default()).id();
+        let press = |app: &mut App, key| {
+            for state in [ButtonState::Pressed, ButtonState::Released] {
+                app.world.send_event(KeyboardInput {
+                    scan_code: 0,
+                    key_code: Some(key),
+                    state,
+                });
+                app.update();
+            }
+        };
+
+        // Rebind the left player's up key
+        press(&mut app, KeyCode::Return);
+        assert!(app.world.get::<ControlsMenu>(menu).unwrap().listening);
+        press(&mut app, KeyCode::T);
+        assert_eq!(
+            app.world.resource::<Bindings>().left.key(Action::MoveUp),
+            KeyCode::T
+        );
+
+        // The right player's up key is taken, so down stays as it was
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Return);
+        press(&mut app, KeyCode::Up);
+        let controls = app.world.get::<ControlsMenu>(menu).unwrap();
+        assert!(!controls.listening);
+        assert_eq!(controls.message, "Up is already Right Up");
+        assert_eq!(
+            app.world.resource::<Bindings>().left.key(Action::MoveDown),
+            KeyCode::S
+        );
+
+        // Escape cancels a rebind instead of being bound, and stays in the menu
+        press(&mut app, KeyCode::Return);
+        press(&mut app, KeyCode::Escape);
+        let controls = app.world.get::<ControlsMenu>(menu).unwrap();
+        assert!(!controls.listening);
+        assert_eq!(controls.message, "Rebind cancelled");
+        assert_eq!(
+            app.world.resource::<Bindings>().left.key(Action::MoveDown),
+            KeyCode::S
+        );
+        assert!(app.world.resource::<NextState<AppState>>().0.is_none());
+
+        // The pause menu's keys can't be bound to pause
+        for _ in 0..4 {
+            press(&mut app, KeyCode::Down);
+        }
+        press(&mut app, KeyCode::Return);
+        press(&mut app, KeyCode::R);
+        assert_eq!(
+            app.world.get::<ControlsMenu>(menu).unwrap().message,
+            "R is used by the pause menu"
+        );
+        assert_eq!(
+            app.world.resource::<Bindings>().left.key(Action::Pause),
+            KeyCode::Escape
+        );
+    }
+}
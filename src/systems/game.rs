@@ -1,24 +1,32 @@
 use bevy::{
     prelude::{
-        info, AssetServer, BuildChildren, Color, Commands, DespawnRecursiveExt, Entity, NextState,
-        Query, Res, ResMut, SpatialBundle, Vec2, With,
+        info, warn, AssetServer, Assets, BuildChildren, Changed, Color, Commands,
+        DespawnRecursiveExt, Entity, NextState, Query, Res, ResMut, SpatialBundle, Text2dBundle,
+        Transform, Vec2, Visibility, With,
     },
-    text::TextStyle,
+    text::{Text, TextAlignment, TextStyle},
 };
 
 use crate::{
+    arena::Arena,
     component::{
+        ai::Ai,
         ball, bounding_box,
         collider::Collider,
+        controls::Controller,
         game::Game,
-        paddle::Side,
+        obstacle::Spawner,
+        paddle::{Role, Side},
+        power_up,
         score::{self, Score},
+        serve::{CountdownBundle, Serving},
+        velocity::{Friction, MaxSpeed},
         wall, Bundle,
     },
-    constants::{
-        BOTTOM_WALL_POSITION, BOTTOM_WALL_SIZE, LEFT_PADDLE_STARTING_POSITION, LEFT_SCORE_POSITION,
-        RIGHT_PADDLE_STARTING_POSITION, RIGHT_SCORE_POSITION, TOP_WALL_POSITION, TOP_WALL_SIZE,
-        WIN_SCORE,
+    constants::MULTIBALL_SPACING,
+    resources::{
+        Arenas, Bindings, MatchResult, MatchRules, MatchStats, Multiball, Opponent, PowerUps,
+        Practice, RallySpeed, Series, Serve, Settings, Teammate,
     },
     states::AppState,
 };
@@ -26,7 +34,25 @@ use crate::{
 /// Spawns all of the entities needed to play a game of Pong. They are spawned
 /// as children of a single Game entity, which makes it easier to despawn all
 /// of the entities at once when finished.
-pub fn initialize_match(mut commands: Commands, asset_server: Res<AssetServer>) {
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_match(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut rally: ResMut<RallySpeed>,
+    mut serve: ResMut<Serve>,
+    mut series: ResMut<Series>,
+    mut stats: ResMut<MatchStats>,
+    rules: Res<MatchRules>,
+    opponent: Res<Opponent>,
+    bindings: Res<Bindings>,
+    settings: Res<Settings>,
+    arenas: Res<Arenas>,
+    arena_assets: Res<Assets<Arena>>,
+    power_ups: Res<PowerUps>,
+    multiball: Res<Multiball>,
+    teammate: Res<Teammate>,
+    practice: Res<Practice>,
+) {
     // Score text style
     let font = asset_server.load("fonts/NotoSansMono-Regular.ttf");
     let score_style = TextStyle {
@@ -35,80 +61,185 @@ pub fn initialize_match(mut commands: Commands, asset_server: Res<AssetServer>)
         color: Color::WHITE,
     };
 
+    // A finished series makes way for a new one, and every practice run
+    // starts afresh
+    let practising = *opponent == Opponent::Wall;
+    if practising || series.winner(&rules).is_some() {
+        series.reset();
+        stats.reset();
+    }
+
+    // The picked arena should have loaded while the menu was up, but play on
+    // the classic court rather than not at all if it hasn't
+    let arena = arenas.selected(&arena_assets).cloned().unwrap_or_else(|| {
+        warn!(
+            "Arena {} isn't loaded, using the classic arena",
+            arenas.selected
+        );
+        Arena::default()
+    });
+    info!("Playing in the {} arena", arena.name);
+
+    // Draw lots for who receives the first serve. When practising, the ball
+    // goes to the wall first.
+    rally.reset();
+    let sides = arena.sides();
+    serve.start_match(if practising {
+        sides
+            .into_iter()
+            .find(|&side| side != Side::Left)
+            .unwrap_or(Side::Right)
+    } else {
+        sides[rand::random::<usize>() % sides.len()]
+    });
+
     // Create a parent Game entity to make it easier to apply setup/teardown logic
     commands
         .spawn((Game, SpatialBundle::default()))
         .with_children(|parent| {
             // paddles
-            parent.spawn(Bundle::left_player().with_position(LEFT_PADDLE_STARTING_POSITION));
-            parent.spawn(Bundle::right_player().with_position(RIGHT_PADDLE_STARTING_POSITION));
+            let tuned = |paddle: Bundle| {
+                paddle
+                    .with_bindings(&bindings)
+                    .with_friction(Friction(settings.paddle.friction))
+                    .with_max_speed(MaxSpeed(settings.paddle.speed))
+            };
+            for spawn in &arena.paddles {
+                // Only the left player turns up to practise
+                if practising && spawn.side != Side::Left {
+                    continue;
+                }
+                let paddle = Bundle::for_side(spawn.side).with_role(spawn.role);
+                let mut paddle = parent.spawn(tuned(paddle).with_position(spawn.position));
+                // The computer can play the left player's teammate, and
+                // everyone on the other sides
+                let computer = match (spawn.side, spawn.role, *opponent) {
+                    (Side::Left, Role::Attacker, _) => teammate.difficulty(),
+                    (Side::Left, Role::Defender, _) | (_, _, Opponent::Human | Opponent::Wall) => {
+                        None
+                    }
+                    (_, _, Opponent::Cpu(difficulty)) => Some(difficulty),
+                };
+                if let Some(difficulty) = computer {
+                    paddle.insert((Controller::Ai, Ai::new(difficulty)));
+                }
+            }
 
             // Paddle bounding boxes
-            parent.spawn(
-                bounding_box::Bundle::default()
-                    .with_visibility(bevy::prelude::Visibility::Visible)
-                    .with_dimensions(TOP_WALL_SIZE.x / 2.0, 500.0)
-                    .with_position(Vec2::new(-125.0, 0.0))
-                    .on_side(Side::Left),
-            );
-            parent.spawn(
-                bounding_box::Bundle::default()
-                    .with_visibility(bevy::prelude::Visibility::Visible)
-                    .with_dimensions(TOP_WALL_SIZE.x / 2.0, 500.0)
-                    .with_position(Vec2::new(125.0, 0.0))
-                    .on_side(Side::Right),
-            );
+            for zone in &arena.paddle_zones {
+                parent.spawn(
+                    bounding_box::Bundle::default()
+                        .with_visibility(Visibility::Visible)
+                        .with_dimensions(zone.size.x, zone.size.y)
+                        .with_position(zone.position)
+                        .on_side(zone.side)
+                        .for_role(zone.role),
+                );
+            }
 
-            // Score counters
-            parent.spawn(
-                score::Bundle::default()
-                    .with_style(score_style.clone())
-                    .side(Side::Left)
-                    .at(LEFT_SCORE_POSITION),
-            );
+            // Score counters. When practising, the left player's counts the
+            // rally, and their personal best is shown where the right
+            // player's score would be.
+            for score in &arena.scores {
+                if practising && score.side != Side::Left {
+                    if score.side == Side::Right {
+                        parent.spawn(Text2dBundle {
+                            text: Text::from_section(
+                                format!("Best: {}", practice.best),
+                                TextStyle {
+                                    font_size: 30.0,
+                                    ..score_style.clone()
+                                },
+                            )
+                            .with_alignment(TextAlignment::Center),
+                            transform: Transform::from_translation(score.position.extend(0.0)),
+                            ..Default::default()
+                        });
+                    }
+                    continue;
+                }
+                let starting_score = if practising {
+                    0
+                } else {
+                    rules.starting_score()
+                };
+                parent.spawn(
+                    score::Bundle::default()
+                        .with_value(starting_score)
+                        .with_style(score_style.clone())
+                        .side(score.side)
+                        .at(score.position),
+                );
+            }
+
+            // Serve countdown
             parent.spawn(
-                score::Bundle::default()
+                CountdownBundle::default()
                     .with_style(score_style)
-                    .side(Side::Right)
-                    .at(RIGHT_SCORE_POSITION),
+                    .at(arena.countdown),
             );
 
-            // ball
-            parent.spawn(ball::Bundle::default());
-
-            // score zones
-            parent.spawn((
-                bounding_box::Bundle::default()
-                    .with_visibility(bevy::prelude::Visibility::Visible)
-                    .with_dimensions(25.0, 500.0)
-                    .with_position(Vec2::new(-250.0, 0.0))
-                    .on_side(Side::Left),
-                Collider::default(),
-                bounding_box::ScoreDetector,
-            ));
-            parent.spawn((
-                bounding_box::Bundle::default()
-                    .with_visibility(bevy::prelude::Visibility::Visible)
-                    .with_dimensions(25.0, 500.0)
-                    .with_position(Vec2::new(250.0, 0.0))
-                    .on_side(Side::Right),
-                Collider::default(),
-                bounding_box::ScoreDetector,
-            ));
-
-            // top and bottom walls
-            parent.spawn(
-                wall::Bundle::default()
-                    .with_size(TOP_WALL_SIZE.x, TOP_WALL_SIZE.y)
-                    .at(TOP_WALL_POSITION)
-                    .visible(), // debug
-            );
-            parent.spawn(
-                wall::Bundle::default()
-                    .with_size(BOTTOM_WALL_SIZE.x, BOTTOM_WALL_SIZE.y)
-                    .at(BOTTOM_WALL_POSITION)
-                    .visible(), // debug
-            );
+            // balls, lined up one above the other
+            let balls = multiball.balls_in_play();
+            for i in 0..balls {
+                let offset = (i as f32 - (balls - 1) as f32 / 2.0) * MULTIBALL_SPACING;
+                parent.spawn((
+                    ball::Bundle::default().with_position(arena.ball + Vec2::new(0.0, offset)),
+                    Serving::new(serve.first, serve.countdown),
+                ));
+            }
+
+            // score zones. When practising, the only one is the left
+            // player's, and the others are walled up.
+            for goal in &arena.goals {
+                if practising && goal.side != Side::Left {
+                    parent.spawn(
+                        wall::Bundle::default()
+                            .with_size(goal.size.x, goal.size.y)
+                            .at(goal.position)
+                            .visible(),
+                    );
+                    continue;
+                }
+                parent.spawn((
+                    bounding_box::Bundle::default()
+                        .with_visibility(Visibility::Visible)
+                        .with_dimensions(goal.size.x, goal.size.y)
+                        .with_position(goal.position)
+                        .on_side(goal.side),
+                    Collider,
+                    bounding_box::ScoreDetector,
+                ));
+            }
+
+            // obstacles
+            for blueprint in &arena.obstacles {
+                let mut obstacle = parent.spawn(blueprint.bundle());
+                if let Some(lifetime) = blueprint.lifetime() {
+                    obstacle.insert(lifetime);
+                }
+            }
+            for timed in &arena.timed_obstacles {
+                parent.spawn(Spawner::every(timed.every, timed.obstacle.clone()));
+            }
+
+            // power-ups
+            if power_ups.enabled {
+                parent.spawn(power_up::Spawner::every(
+                    power_ups.every,
+                    arena.power_up_area,
+                ));
+            }
+
+            // walls
+            for wall in &arena.walls {
+                parent.spawn(
+                    wall::Bundle::default()
+                        .with_size(wall.size.x, wall.size.y)
+                        .at(wall.position)
+                        .visible(), // debug
+                );
+            }
         });
 }
 
@@ -128,22 +259,51 @@ pub fn no_active_match(game_query: Query<Entity, With<Game>>) -> bool {
     game_query.is_empty()
 }
 
-/// Checks if a player has won the game. If a player has won, the game state
-/// transitions to the main menu.
+/// Checks if a player has won the game under the match rules. The win counts
+/// toward the series; if the series isn't over yet, the next game is set up,
+/// otherwise the result is recorded and the game state transitions to the
+/// results screen. Practice runs aren't won; they end on the first miss, see
+/// `end_practice_run`.
 pub fn detect_win_condition(
-    players_query: Query<(Entity, &Score)>,
+    mut commands: Commands,
+    scores_query: Query<&Score>,
+    changed_query: Query<(), Changed<Score>>,
+    rules: Res<MatchRules>,
+    opponent: Res<Opponent>,
+    mut series: ResMut<Series>,
     mut state: ResMut<NextState<AppState>>,
 ) {
-    let winners: Vec<Entity> = players_query
+    if *opponent == Opponent::Wall {
+        return;
+    }
+
+    // Only a new point can win a game, and this keeps a win from being counted
+    // again on later steps before the state transition happens
+    if changed_query.is_empty() {
+        return;
+    }
+
+    let mut scores: Vec<(Side, u64)> = scores_query
         .iter()
-        .filter(|(_, score)| score.value >= WIN_SCORE)
-        .map(|(id, _)| id)
+        .map(|score| (score.side, score.value))
         .collect();
+    scores.sort_by_key(|&(side, _)| Side::ALL.iter().position(|&s| s == side));
+    let Some(winner) = rules.game_winner(&scores) else {
+        return;
+    };
 
-    assert!(winners.len() <= 1, "Multiple winners!");
+    series.record_win(winner);
+    info!("Game won by {:?}, series is {:?}", winner, *series);
 
-    if winners.len() == 1 {
-        info!("Winner: {:?}", winners[0]);
-        state.set(AppState::MainMenu);
+    if let Some(series_winner) = series.winner(&rules) {
+        info!("Series won by {:?}", series_winner);
+        commands.insert_resource(MatchResult {
+            winner: series_winner,
+            scores,
+        });
+        state.set(AppState::GameOver);
+    } else {
+        // Re-entering the state tears this game down and sets up the next one
+        state.set(AppState::InGame);
     }
 }
@@ -0,0 +1,166 @@
+use bevy::{
+    prelude::{
+        debug, info, AssetServer, BuildChildren, Color, Commands, DespawnRecursiveExt, Entity,
+        Input, KeyCode, NextState, Query, Res, ResMut, SpatialBundle, Text2dBundle, Transform,
+        With,
+    },
+    text::{Text, TextAlignment, TextStyle},
+};
+
+use crate::{
+    component::game_over::GameOverScreen,
+    resources::{Breakout, MatchResult, MatchRules, MatchStats, Opponent, Practice, Series},
+    states::AppState,
+};
+
+/// Shows who won the match, the final score, and a few stats from the match.
+/// After wall practice, it shows how long the run lasted instead, and after
+/// Breakout, the points scored.
+#[allow(clippy::too_many_arguments)]
+pub fn setup_game_over(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    result: Option<Res<MatchResult>>,
+    rules: Res<MatchRules>,
+    series: Res<Series>,
+    stats: Res<MatchStats>,
+    opponent: Res<Opponent>,
+    practice: Res<Practice>,
+    breakout: Res<Breakout>,
+) {
+    info!("Now in game over screen");
+
+    let font = asset_server.load("fonts/NotoSansMono-Regular.ttf");
+    let title_style = TextStyle {
+        font: font.clone(),
+        font_size: 60.0,
+        color: Color::WHITE,
+    };
+    let body_style = TextStyle {
+        font,
+        font_size: 30.0,
+        color: Color::WHITE,
+    };
+
+    let (title, mut lines) = match result {
+        _ if breakout.playing => breakout_summary(&breakout),
+        Some(result) if *opponent != Opponent::Wall => {
+            let (title, mut lines) = match_summary(&result, &rules, &series);
+            lines.push(String::new());
+            lines.push(format!("Points played: {}", stats.points_played));
+            lines.push(format!("Longest rally: {} hits", stats.longest_rally));
+            (title, lines)
+        }
+        _ => practice_summary(&practice),
+    };
+    lines.push(format!("Top speed: {:.0}", stats.top_speed));
+
+    commands
+        .spawn((GameOverScreen, SpatialBundle::default()))
+        .with_children(|parent| {
+            parent.spawn(Text2dBundle {
+                text: Text::from_section(title, title_style).with_alignment(TextAlignment::Center),
+                transform: Transform::from_xyz(0.0, 150.0, 0.0),
+                ..Default::default()
+            });
+
+            parent.spawn(Text2dBundle {
+                text: Text::from_section(lines.join("\n"), body_style.clone())
+                    .with_alignment(TextAlignment::Center),
+                transform: Transform::from_xyz(0.0, 0.0, 0.0),
+                ..Default::default()
+            });
+
+            parent.spawn(Text2dBundle {
+                text: Text::from_section("R - Rematch    M - Main menu", body_style)
+                    .with_alignment(TextAlignment::Center),
+                transform: Transform::from_xyz(0.0, -175.0, 0.0),
+                ..Default::default()
+            });
+        });
+}
+
+/// The title and score lines for a finished match.
+fn match_summary(
+    result: &MatchResult,
+    rules: &MatchRules,
+    series: &Series,
+) -> (String, Vec<String>) {
+    // Two scores read as a scoreline; any more need the players named
+    let joined = |values: Vec<String>| values.join(" - ");
+    let mut lines = if result.scores.len() > 2 {
+        result
+            .scores
+            .iter()
+            .map(|(side, score)| format!("{side:?}: {score}"))
+            .collect()
+    } else {
+        vec![joined(
+            result.scores.iter().map(|(_, s)| s.to_string()).collect(),
+        )]
+    };
+    if rules.games_in_series > 1 {
+        let wins = result
+            .scores
+            .iter()
+            .map(|&(side, _)| series.wins(side).to_string())
+            .collect();
+        lines.push(format!("Games {}", joined(wins)));
+    }
+    (format!("{:?} wins!", result.winner), lines)
+}
+
+/// The title and score lines for a finished practice run.
+fn practice_summary(practice: &Practice) -> (String, Vec<String>) {
+    let title = if practice.new_best {
+        "New best!"
+    } else {
+        "Missed!"
+    };
+    let lines = vec![
+        format!("Rally: {} hits", practice.last),
+        format!("Personal best: {} hits", practice.best),
+        String::new(),
+    ];
+    (title.to_string(), lines)
+}
+
+/// The title and score lines for a finished Breakout run.
+fn breakout_summary(breakout: &Breakout) -> (String, Vec<String>) {
+    let title = if breakout.cleared {
+        "All levels cleared!"
+    } else if breakout.new_best {
+        "New high score!"
+    } else {
+        "Game over"
+    };
+    let lines = vec![
+        format!("Score: {}", breakout.score),
+        format!("Levels cleared: {}", breakout.level),
+        format!("High score: {}", breakout.best),
+        String::new(),
+    ];
+    (title.to_string(), lines)
+}
+
+/// Starts a rematch with the same rules, or goes back to the main menu.
+pub fn read_game_over_keypresses(
+    keys: Res<Input<KeyCode>>,
+    mut state: ResMut<NextState<AppState>>,
+) {
+    keys.get_just_pressed().for_each(|key| {
+        debug!("Key pressed: {:?}", key);
+        match key {
+            KeyCode::R => state.set(AppState::InGame),
+            KeyCode::M => state.set(AppState::MainMenu),
+            _ => {}
+        }
+    });
+}
+
+pub fn teardown_game_over(query: Query<Entity, With<GameOverScreen>>, mut commands: Commands) {
+    info!("Now leaving game over screen");
+
+    let ent = query.single();
+    commands.entity(ent).despawn_recursive();
+}
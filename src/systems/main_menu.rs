@@ -1,18 +1,29 @@
 use bevy::{
     prelude::{
-        debug, info, AssetServer, BuildChildren, Color, Commands, DespawnRecursiveExt, Entity,
-        Input, KeyCode, NextState, Query, Res, ResMut, SpatialBundle, Text2dBundle, Transform,
-        With,
+        debug, info, AssetServer, Assets, BuildChildren, Color, Commands, DespawnRecursiveExt,
+        DetectChanges, Entity, Input, KeyCode, NextState, Query, Res, ResMut, SpatialBundle,
+        Text2dBundle, Transform, With,
     },
     text::{Text, TextAlignment, TextStyle},
 };
 
-use crate::{component::main_menu::MainMenu, states::AppState};
+use crate::{
+    arena::Arena,
+    component::main_menu::{ArenaText, MainMenu, MultiballText, OpponentText, TeammateText},
+    resources::{Arenas, Breakout, Multiball, Opponent, Settings, Teammate},
+    states::AppState,
+};
 
+#[allow(clippy::too_many_arguments)]
 pub fn setup_main_menu(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     menu_query: Query<&MainMenu>,
+    opponent: Res<Opponent>,
+    arenas: Res<Arenas>,
+    arena_assets: Res<Assets<Arena>>,
+    multiball: Res<Multiball>,
+    teammate: Res<Teammate>,
 ) {
     info!("Now in main menu");
 
@@ -40,28 +51,179 @@ pub fn setup_main_menu(
             parent.spawn(Text2dBundle {
                 text: Text::from_section("P O N G", title_style.clone())
                     .with_alignment(TextAlignment::Center),
-                transform: Transform::from_xyz(0.0, 100.0, 0.0),
+                transform: Transform::from_xyz(0.0, 170.0, 0.0),
+                ..Default::default()
+            });
+
+            parent.spawn((
+                OpponentText,
+                Text2dBundle {
+                    text: Text::from_section(opponent_label(&opponent), prompt_style.clone())
+                        .with_alignment(TextAlignment::Center),
+                    transform: Transform::from_xyz(0.0, 80.0, 0.0),
+                    ..Default::default()
+                },
+            ));
+
+            parent.spawn((
+                ArenaText,
+                Text2dBundle {
+                    text: Text::from_section(
+                        arena_label(&arenas, &arena_assets),
+                        prompt_style.clone(),
+                    )
+                    .with_alignment(TextAlignment::Center),
+                    transform: Transform::from_xyz(0.0, 30.0, 0.0),
+                    ..Default::default()
+                },
+            ));
+
+            parent.spawn((
+                MultiballText,
+                Text2dBundle {
+                    text: Text::from_section(multiball_label(&multiball), prompt_style.clone())
+                        .with_alignment(TextAlignment::Center),
+                    transform: Transform::from_xyz(0.0, -20.0, 0.0),
+                    ..Default::default()
+                },
+            ));
+
+            parent.spawn((
+                TeammateText,
+                Text2dBundle {
+                    text: Text::from_section(teammate_label(&teammate), prompt_style.clone())
+                        .with_alignment(TextAlignment::Center),
+                    transform: Transform::from_xyz(0.0, -70.0, 0.0),
+                    ..Default::default()
+                },
+            ));
+
+            parent.spawn(Text2dBundle {
+                text: Text::from_section("Spacebar: Play    B: Breakout", prompt_style.clone())
+                    .with_alignment(TextAlignment::Center),
+                transform: Transform::from_xyz(0.0, -120.0, 0.0),
                 ..Default::default()
             });
 
             parent.spawn(Text2dBundle {
-                text: Text::from_section("Press Spacebar to play", prompt_style.clone())
+                text: Text::from_section("Press C to change controls", prompt_style.clone())
                     .with_alignment(TextAlignment::Center),
-                transform: Transform::from_xyz(0.0, -100.0, 0.0),
+                transform: Transform::from_xyz(0.0, -170.0, 0.0),
                 ..Default::default()
             });
         });
 }
 
-pub fn read_keypresses(keys: Res<Input<KeyCode>>, mut state: ResMut<NextState<AppState>>) {
+/// The main menu text for picking an opponent.
+fn opponent_label(opponent: &Opponent) -> String {
+    format!("< {} >", opponent.name())
+}
+
+/// The main menu text for picking an arena.
+fn arena_label(arenas: &Arenas, assets: &Assets<Arena>) -> String {
+    let name = arenas
+        .selected(assets)
+        .map_or("Loading...", |arena| arena.name.as_str());
+    format!("^ Arena: {name} v")
+}
+
+/// The main menu text for turning multiball mode on and off.
+fn multiball_label(multiball: &Multiball) -> String {
+    let state = if multiball.enabled { "on" } else { "off" };
+    format!("M: Multiball {state}")
+}
+
+/// The main menu text for picking a teammate in doubles.
+fn teammate_label(teammate: &Teammate) -> String {
+    format!("T: Doubles partner - {}", teammate.name())
+}
+
+pub fn read_keypresses(
+    keys: Res<Input<KeyCode>>,
+    mut state: ResMut<NextState<AppState>>,
+    mut opponent: ResMut<Opponent>,
+    mut arenas: ResMut<Arenas>,
+    mut teammate: ResMut<Teammate>,
+    mut breakout: ResMut<Breakout>,
+    mut settings: ResMut<Settings>,
+) {
     keys.get_just_pressed().for_each(|key| {
         debug!("Key pressed: {:?}", key);
-        if key == &KeyCode::Space {
-            state.set(AppState::InGame);
+        match key {
+            KeyCode::Space => {
+                breakout.playing = false;
+                state.set(AppState::InGame);
+            }
+            KeyCode::B => {
+                breakout.start();
+                state.set(AppState::InGame);
+            }
+            KeyCode::C => state.set(AppState::Controls),
+            KeyCode::Left => *opponent = opponent.previous(),
+            KeyCode::Right => *opponent = opponent.next(),
+            KeyCode::Up => arenas.previous(),
+            KeyCode::Down => arenas.next(),
+            KeyCode::M => settings.multiball.enabled = !settings.multiball.enabled,
+            KeyCode::T => *teammate = teammate.next(),
+            _ => {}
         }
     });
 }
 
+/// Keeps the opponent shown in the main menu up to date.
+pub fn update_opponent_text(
+    opponent: Res<Opponent>,
+    mut text_q: Query<&mut Text, With<OpponentText>>,
+) {
+    if !opponent.is_changed() {
+        return;
+    }
+    for mut text in &mut text_q {
+        text.sections[0].value = opponent_label(&opponent);
+    }
+}
+
+/// Keeps the arena shown in the main menu up to date, including when the
+/// picked arena finishes loading.
+pub fn update_arena_text(
+    arenas: Res<Arenas>,
+    arena_assets: Res<Assets<Arena>>,
+    mut text_q: Query<&mut Text, With<ArenaText>>,
+) {
+    if !arenas.is_changed() && !arena_assets.is_changed() {
+        return;
+    }
+    for mut text in &mut text_q {
+        text.sections[0].value = arena_label(&arenas, &arena_assets);
+    }
+}
+
+/// Keeps whether multiball mode is on up to date in the main menu.
+pub fn update_multiball_text(
+    multiball: Res<Multiball>,
+    mut text_q: Query<&mut Text, With<MultiballText>>,
+) {
+    if !multiball.is_changed() {
+        return;
+    }
+    for mut text in &mut text_q {
+        text.sections[0].value = multiball_label(&multiball);
+    }
+}
+
+/// Keeps the doubles partner shown in the main menu up to date.
+pub fn update_teammate_text(
+    teammate: Res<Teammate>,
+    mut text_q: Query<&mut Text, With<TeammateText>>,
+) {
+    if !teammate.is_changed() {
+        return;
+    }
+    for mut text in &mut text_q {
+        text.sections[0].value = teammate_label(&teammate);
+    }
+}
+
 pub fn teardown_main_menu(query: Query<Entity, With<MainMenu>>, mut commands: Commands) {
     info!("Now leaving main menu");
 
@@ -2,37 +2,64 @@ use std::ops::Mul;
 
 use bevy::{
     core_pipeline::bloom::BloomSettings,
+    ecs::schedule::SystemConfigs,
     prelude::{
         debug, info, AssetServer, Assets, Audio, AudioSink, AudioSinkPlayback, Camera,
-        Camera2dBundle, Commands, Entity, EventReader, EventWriter, Handle, Input, KeyCode,
-        ParamSet, Query, Res, ResMut, Resource, Transform, Vec2, With, Without,
+        Camera2dBundle, Commands, DespawnRecursiveExt, Entity, EventReader, EventWriter, FixedTime,
+        Handle, IntoSystemConfigs, ParamSet, PlaybackSettings, Quat, Query, Res, ResMut, Resource,
+        Transform, Vec2, Vec3, Visibility, With, Without,
     },
     sprite::collide_aabb::{collide, Collision},
     text::Text,
     time::{Time, Timer},
-    utils::HashMap,
 };
 
 use crate::{
     component::{
-        ball::Ball,
+        ai::{most_urgent, transpose, Ai},
+        ball::{Ball, Extra, LastHit, Out, SpeedFactor, StartingPosition, Stuck},
         bounding_box::{self, is_completely_inside_bounds, is_inside_bounds, BoundingBox},
-        collider::{self, Collider},
-        controls::Keyboard,
+        collider::{self, Collider, Hull},
+        controls::{Action, ActionState},
         paddle::Player,
+        power_up::Reversed,
         score::Score,
-        velocity::{Friction, Velocity},
+        serve::{CountdownText, Serving},
+        spin::Spin,
+        velocity::{Friction, MaxSpeed, Velocity},
+        wall::Wall,
     },
-    constants::{BALL_DEFAULT_STARTING_POSITION, PADDLE_SPEED_MULTIPLIER, TIME_STEP},
+    constants::MAX_BALL_BOUNCES_PER_STEP,
     events::score,
     plugins::shake,
+    resources::{
+        Deflection, MatchRules, MatchStats, PointEnds, RallySpeed, Serve, Settings, Victory,
+    },
 };
 
+mod breakout;
+mod controls;
+mod controls_menu;
 mod game;
+mod game_over;
 mod main_menu;
+mod obstacles;
+mod pause;
+mod power_ups;
+mod practice;
+mod settings;
 
+pub use breakout::*;
+pub use controls::*;
+pub use controls_menu::*;
 pub use game::*;
+pub use game_over::*;
 pub use main_menu::*;
+pub use obstacles::*;
+pub use pause::*;
+pub use power_ups::*;
+pub use practice::*;
+pub use settings::*;
 
 /// Creates a camera with a bloom effect for a retro look.
 pub fn spawn_camera(mut commands: Commands) {
@@ -52,6 +79,48 @@ pub fn spawn_camera(mut commands: Commands) {
     ));
 }
 
+/// The systems that advance the match by one fixed step. They are chained so
+/// that input, movement, collisions and scoring always happen in the same
+/// order, which keeps the simulation deterministic.
+pub fn simulation_systems() -> SystemConfigs {
+    (
+        ai_input,
+        paddle_input,
+        move_paddles,
+        apply_friction,
+        serve_ball,
+        apply_spin,
+        collide_ball,
+        stick_ball,
+        collect_power_ups,
+        detect_score,
+        handle_score_event,
+        record_match_stats,
+        reset_rally,
+        detect_win_condition,
+    )
+        .chain()
+}
+
+/// The systems that put things on the court and take them off again, and move
+/// the things that aren't paddles or balls. They run after the ball has been
+/// served and spun, but before it moves, as part of the same fixed step as
+/// `simulation_systems`.
+pub fn arena_systems() -> SystemConfigs {
+    (
+        spawn_timed_obstacles,
+        expire_obstacles,
+        move_obstacles,
+        spawn_power_ups,
+        expire_power_ups,
+        expire_effects,
+        hold_stuck_ball,
+    )
+        .chain()
+        .after(apply_spin)
+        .before(collide_ball)
+}
+
 #[derive(Resource)]
 pub struct LogSamplingTimer(pub Timer);
 
@@ -67,33 +136,111 @@ pub fn log_game_state(
     }
 }
 
-/// Change the velocity of the paddle based on the player input
+/// Change the velocity of the paddle based on what its controller is asking
+/// for. Movement actions and analog movement are blended into a single Vec2,
+/// to allow for diagonal movement. A paddle with a target heads straight for
+/// it, as fast as its speed limit allows, and stops once it gets there. A
+/// reversed paddle goes the opposite way to what it is asked.
+#[allow(clippy::type_complexity)]
 pub fn paddle_input(
-    keys: Res<Input<KeyCode>>,
-    mut paddle_q: Query<(Entity, &mut Velocity, &Keyboard), With<Player>>,
+    mut paddle_q: Query<
+        (
+            &Transform,
+            &mut Velocity,
+            &MaxSpeed,
+            &ActionState,
+            Option<&Reversed>,
+        ),
+        With<Player>,
+    >,
+    fixed_time: Res<FixedTime>,
 ) {
-    let mut player_vecs: HashMap<Entity, Vec<Vec2>> = HashMap::new();
-    // Map Entity ID to the set of Vec2s produced by the key inputs
-    for (entity, _, controls) in paddle_q.iter_mut() {
-        let vecs: Vec<Vec2> = keys
-            .get_pressed()
-            .filter_map(|k| controls.calculate_vec2(k))
-            .collect();
-        if !vecs.is_empty() {
-            player_vecs.insert(entity, vecs);
+    let step = fixed_time.period.as_secs_f32();
+    for (tf, mut vel, max_speed, actions, reversed) in &mut paddle_q {
+        let direction = if reversed.is_some() { -1.0 } else { 1.0 };
+
+        if let Some(target) = actions.target() {
+            let offset = (target - tf.translation.truncate()) * direction;
+            *vel = (offset / step).clamp_length_max(**max_speed).into();
+            continue;
+        }
+
+        let movement = actions.movement() * direction;
+        if movement != Vec2::ZERO {
+            *vel = movement.mul(**max_speed).into();
         }
     }
+}
 
-    // Blend the inputs into a single Vec2 for each paddle, to allow for
-    // diagonal movement
-    for (entity, mut vel, _) in paddle_q.iter_mut() {
-        if let Some(vecs) = player_vecs.get(&entity) {
-            *vel = vecs
-                .iter()
-                .fold(Vec2::ZERO, |acc, v| acc + *v)
-                .mul(PADDLE_SPEED_MULTIPLIER)
-                .into();
+/// Lets the computer move the paddles it controls. Each AI only looks at the
+/// ball as often as its reaction time allows, and steers toward wherever it
+/// last decided the ball was going, by moving its paddle the same way a
+/// player's stick would. With more than one ball in play, it goes for the one
+/// that will reach it first. Paddles that move left and right see the court
+/// transposed, so the AI can treat every paddle as if it moved up and down.
+#[allow(clippy::type_complexity)]
+pub fn ai_input(
+    mut paddle_q: Query<
+        (
+            &Transform,
+            &Velocity,
+            &Friction,
+            &MaxSpeed,
+            &Player,
+            &mut Ai,
+            &mut ActionState,
+        ),
+        With<Player>,
+    >,
+    ball_q: Query<(&Transform, &Velocity), (With<Ball>, Without<Player>, Without<Out>)>,
+    wall_q: Query<&Transform, (With<Wall>, Without<Player>, Without<Ball>)>,
+    fixed_time: Res<FixedTime>,
+) {
+    let step = fixed_time.period.as_secs_f32();
+    let walls: Vec<Transform> = wall_q.iter().copied().collect();
+    let transposed_walls: Vec<Transform> = walls.iter().map(transpose).collect();
+    let transposed_balls: Vec<(Transform, Vec2)> = ball_q
+        .iter()
+        .map(|(tf, vel)| (transpose(tf), Vec2::new(vel.y, vel.x)))
+        .collect();
+
+    for (tf, vel, friction, max_speed, player, mut ai, mut actions) in &mut paddle_q {
+        let upright = player.side.moves_vertically();
+        let (tf, vel, walls) = if upright {
+            (*tf, **vel, &walls)
+        } else {
+            (transpose(tf), Vec2::new(vel.y, vel.x), &transposed_walls)
+        };
+        let urgent = if upright {
+            most_urgent(
+                &tf,
+                ball_q
+                    .iter()
+                    .map(|(ball_tf, ball_vel)| (ball_tf, **ball_vel)),
+            )
+        } else {
+            most_urgent(
+                &tf,
+                transposed_balls
+                    .iter()
+                    .map(|(ball_tf, ball_vel)| (ball_tf, *ball_vel)),
+            )
+        };
+        let Some((ball_tf, ball_vel)) = urgent else {
+            return;
+        };
+        if ai.tick(fixed_time.period) {
+            let noise = rand::random::<f32>().mul_add(2.0, -1.0);
+            ai.retarget(&tf, ball_tf, ball_vel, walls, noise);
         }
+        let speed = ai.steer(tf.translation.y, vel.y, **friction, step) / **max_speed;
+        actions.begin_frame();
+        actions.clear();
+        actions.set_axis(if upright {
+            Vec2::new(0.0, speed)
+        } else {
+            Vec2::new(speed, 0.0)
+        });
     }
 }
 
@@ -138,23 +285,14 @@ fn check_collision(
     })
 }
 
-/// Changes the position of the ball according to its velocity
-pub fn move_ball(mut ball_q: Query<(&mut Transform, &Velocity), With<Ball>>) {
-    ball_q.iter_mut().for_each(|(mut tf, vel)| {
-        let scaled_vel = vel.mul(TIME_STEP);
-        info!("moving {:?} by {:?}", tf.translation, scaled_vel);
-
-        tf.translation.x += scaled_vel.x;
-        tf.translation.y += scaled_vel.y;
-    });
-}
-
 pub fn move_paddles(
     mut paddle_q: Query<(&mut Transform, &Velocity, &Player), Without<BoundingBox>>,
     bounds: Query<(&Transform, &BoundingBox)>,
+    fixed_time: Res<FixedTime>,
 ) {
+    let step = fixed_time.period.as_secs_f32();
     paddle_q.iter_mut().for_each(|(mut tf, vel, player)| {
-        let scaled_vel = vel.mul(TIME_STEP);
+        let scaled_vel = vel.mul(step);
         info!("moving {:?} by {:?}", tf.translation, scaled_vel);
 
         let new_pos = {
@@ -166,7 +304,10 @@ pub fn move_paddles(
         // If it is associated with a bounding box, check if it is inside the bounds
         // TODO: it'd be cooler if the BoundingBox could be in the same bundle
         // as the player, maybe?
-        if let Some((bounds_tf, _)) = bounds.iter().find(|(_, bb)| bb.side == player.side) {
+        if let Some((bounds_tf, _)) = bounds
+            .iter()
+            .find(|(_, bb)| bb.side == player.side && bb.role == player.role)
+        {
             if is_completely_inside_bounds(bounds_tf, &new_pos) {
                 tf.translation.x += scaled_vel.x;
                 tf.translation.y += scaled_vel.y;
@@ -178,125 +319,466 @@ pub fn move_paddles(
     });
 }
 
-type PositionAndMaybeVelocity<'a> = (Entity, &'a Transform, Option<&'a Velocity>);
+/// Curves the ball's path according to its spin, and turns the ball so that
+/// the spin can be seen.
+pub fn apply_spin(
+    mut ball_q: Query<(&mut Transform, &mut Velocity, &mut Spin), With<Ball>>,
+    fixed_time: Res<FixedTime>,
+) {
+    let step = fixed_time.period.as_secs_f32();
+    for (mut tf, mut vel, mut spin) in &mut ball_q {
+        *vel = spin.curve(**vel, step).into();
+        spin.decay(step);
+        tf.rotate_z(**spin * step);
+    }
+}
+
+type ColliderComponents<'a> = (
+    Entity,
+    &'a Transform,
+    Option<&'a Velocity>,
+    Option<&'a bounding_box::ScoreDetector>,
+    Option<&'a Player>,
+    Option<&'a Hull>,
+);
 type IsColliderButIsNotBall = (With<Collider>, Without<Ball>);
 
-// TODO: remove if not used
-// /// Checks if the ball collides with a Collider. If it does, it sends a collision
-// /// event and reflects the ball according to the collision angle.
+/// Moves each ball in play through one fixed step, bouncing it off any
+/// Collider in its way. Balls don't collide with each other. Each ball's path
+/// is swept against every collider, so it can't tunnel through a paddle or
+/// wall no matter how fast it is moving. Score zones don't bounce the ball; it
+/// stops as soon as it enters one so that `detect_score` can see it.
+#[allow(clippy::type_complexity)]
 pub fn collide_ball(
-    mut ball_query: Query<(&Transform, &mut Velocity), With<Ball>>,
-    collider_query: Query<PositionAndMaybeVelocity, IsColliderButIsNotBall>,
+    mut ball_query: Query<
+        (
+            &mut Transform,
+            &mut Velocity,
+            &mut Spin,
+            &SpeedFactor,
+            &mut LastHit,
+        ),
+        (With<Ball>, Without<Out>),
+    >,
+    collider_query: Query<ColliderComponents, IsColliderButIsNotBall>,
+    fixed_time: Res<FixedTime>,
+    deflection: Res<Deflection>,
+    mut rally: ResMut<RallySpeed>,
     mut ev_writer: EventWriter<collider::Event>,
     mut screen_shake_writer: EventWriter<shake::Event>,
 ) {
-    let (ball_tf, mut ball_vel) = ball_query.single_mut();
-    let ball_size = ball_tf.scale.truncate();
-
-    for (_, collider_tf, maybe_vel) in &collider_query {
-        if let Some(collision) = collide(
-            ball_tf.translation,
-            ball_size,
-            collider_tf.translation,
-            collider_tf.scale.truncate(),
-        ) {
-            debug!(
-                "Collision between {:?} and {:?}: {:?}",
-                ball_tf, collider_tf, collision
+    for (mut ball_tf, mut ball_vel, mut spin, speed_factor, mut last_hit) in &mut ball_query {
+        // Push the ball out of anything it overlaps, e.g. a paddle that moved
+        // into it. It only bounces if it was heading into the surface, so it
+        // bounces once per contact instead of every step until it gets free.
+        for collider in &collider_query {
+            let (_, collider_tf, _, score_detector, _, hull) = collider;
+            if score_detector.is_some() {
+                continue;
+            }
+
+            let contact = hull.map_or_else(
+                || collider::contact(&ball_tf, collider_tf),
+                |hull| {
+                    hull.contact(
+                        collider_tf,
+                        ball_tf.translation.truncate(),
+                        ball_tf.scale.x / 2.0,
+                    )
+                },
             );
+            if let Some(contact) = contact {
+                debug!(
+                    "Ball overlapping {:?} by {:?}",
+                    collider_tf.translation, contact
+                );
 
-            let mut reflect_x = false;
-            let mut reflect_y = false;
+                ball_tf.translation += contact.translation().extend(0.0);
 
-            match collision {
-                Collision::Left => reflect_x = ball_vel.x > 0.0,
-                Collision::Right => reflect_x = ball_vel.x < 0.0,
-                Collision::Top => reflect_y = ball_vel.y < 0.0,
-                Collision::Bottom => reflect_y = ball_vel.y > 0.0,
-                Collision::Inside => { /* */ }
+                if (**ball_vel - surface_velocity(collider)).dot(contact.normal) < 0.0 {
+                    let collision_event = bounce_ball(
+                        &ball_tf,
+                        &mut ball_vel,
+                        &mut spin,
+                        contact.normal,
+                        collider,
+                        &deflection,
+                        &mut rally,
+                    );
+                    ev_writer.send(collision_event);
+                    screen_shake_writer.send(shake::Event::from(collision_event));
+                    record_hit(&mut last_hit, collider);
+                }
             }
+        }
+
+        // Sweep the ball along its path for the rest of the step, bouncing
+        // off the first thing it hits and carrying on with whatever distance
+        // is left.
+        let mut remaining = fixed_time.period.as_secs_f32();
+        for _ in 0..MAX_BALL_BOUNCES_PER_STEP {
+            let displacement = **ball_vel * **speed_factor * remaining;
 
-            let collision_event = maybe_vel.map_or_else(collider::Event::default, |vel| {
-                collider::Event::new(collision, **vel, **ball_vel)
-            });
+            let earliest = collider_query
+                .iter()
+                .filter_map(|collider| {
+                    let (_, collider_tf, _, score_detector, _, hull) = collider;
+                    let hit = if let Some(hull) = hull {
+                        hull.sweep(
+                            collider_tf,
+                            ball_tf.translation.truncate(),
+                            ball_tf.scale.x / 2.0,
+                            displacement,
+                        )
+                    } else if score_detector.is_some() {
+                        if is_inside_bounds(collider_tf, &ball_tf) {
+                            return None;
+                        }
+                        // Score zones only need to be entered, so it is enough
+                        // to sweep the center of the ball.
+                        collider::sweep(&ball_tf.with_scale(Vec3::ZERO), displacement, collider_tf)
+                    } else {
+                        collider::sweep(&ball_tf, displacement, collider_tf)
+                    };
+                    hit.map(|hit| (hit, collider))
+                })
+                .min_by(|(a, _), (b, _)| a.time.total_cmp(&b.time));
 
-            ev_writer.send(collision_event.clone());
+            let Some((hit, collider)) = earliest else {
+                ball_tf.translation += displacement.extend(0.0);
+                break;
+            };
 
-            if reflect_x {
-                ball_vel.x = -ball_vel.x;
+            ball_tf.translation += (displacement * hit.time).extend(0.0);
+            if collider.3.is_some() {
+                // Entered a score zone
+                break;
             }
 
-            if reflect_y {
-                ball_vel.y = -ball_vel.y;
-            }
+            debug!("Ball hit {:?} at {:?}", hit, ball_tf.translation);
 
+            let collision_event = bounce_ball(
+                &ball_tf,
+                &mut ball_vel,
+                &mut spin,
+                hit.normal,
+                collider,
+                &deflection,
+                &mut rally,
+            );
+            ev_writer.send(collision_event);
             screen_shake_writer.send(shake::Event::from(collision_event));
+            record_hit(&mut last_hit, collider);
+
+            remaining *= 1.0 - hit.time;
         }
     }
 }
 
-/// Checks if the ball is inside a score zone. If it is, it sends a score event
-/// based on the side of the score zone.
+/// Remembers which paddle hit the ball, if it was a paddle.
+const fn record_hit(last_hit: &mut LastHit, (paddle, _, _, _, player, _): ColliderComponents) {
+    if let Some(player) = player {
+        *last_hit = LastHit {
+            side: Some(player.side),
+            paddle: Some(paddle),
+        };
+    }
+}
+
+/// How fast a collider's surface is moving, as far as bouncing the ball off
+/// it goes. Paddles deflect the ball on their own terms, so only other moving
+/// colliders, like obstacles, count.
+fn surface_velocity((_, _, collider_vel, _, player, _): ColliderComponents) -> Vec2 {
+    match (collider_vel, player) {
+        (Some(vel), None) => **vel,
+        _ => Vec2::ZERO,
+    }
+}
+
+/// Bounces the ball off a surface with the given normal, and returns an event
+/// describing the collision. Paddles deflect the ball according to where it
+/// struck them, speed it up and put spin on it as they sweep across it;
+/// anything else reflects it, with some of its spin turned into sideways speed.
+fn bounce_ball(
+    ball_tf: &Transform,
+    ball_vel: &mut Velocity,
+    spin: &mut Spin,
+    normal: Vec2,
+    collider: ColliderComponents,
+    deflection: &Deflection,
+    rally: &mut RallySpeed,
+) -> collider::Event {
+    let (struck, collider_tf, collider_vel, _, player, _) = collider;
+    let collision_event = collider_vel
+        .map_or_else(
+            || collider::Event::default().with_normal(normal),
+            |vel| collider::Event::new(normal, **vel, **ball_vel),
+        )
+        .with_struck(struck);
+
+    let collider_vel = collider_vel.map_or(Vec2::ZERO, |vel| **vel);
+    let deflected = player.and_then(|_| {
+        deflection.deflect(
+            ball_tf.translation.truncate(),
+            **ball_vel,
+            collider_tf,
+            collider_vel,
+            normal,
+        )
+    });
+
+    *ball_vel = if let Some(deflected) = deflected {
+        spin.impart(normal, collider_vel);
+        deflected.normalize_or_zero() * rally.hit(deflected.length())
+    } else {
+        // Something moving into the ball throws it back faster
+        let radius = ball_tf.scale.x / 2.0;
+        let push = normal * surface_velocity(collider).dot(normal).max(0.0);
+        spin.bounce(
+            collider::reflect(**ball_vel - push, normal) + push,
+            normal,
+            radius,
+        )
+    }
+    .into();
+
+    collision_event
+}
+
+/// Checks whether any ball in play is inside a score zone, and sends a score
+/// event if that ends the point. The point goes to whoever hit the ball last,
+/// unless it went into their own goal, in which case it goes to the side
+/// opposite. Under the `PointEnds::LastBall` rule, a ball that isn't the last
+/// one in play sits out the rest of the point instead.
+#[allow(clippy::type_complexity)]
 pub fn detect_score(
-    ball_query: Query<&Transform, With<Ball>>,
+    mut commands: Commands,
+    mut ball_query: Query<
+        (Entity, &Transform, &mut Velocity, &mut Visibility, &LastHit),
+        (With<Ball>, Without<Out>),
+    >,
     score_zones: Query<(&Transform, &BoundingBox), With<bounding_box::ScoreDetector>>,
+    rules: Res<MatchRules>,
     mut ev_score: EventWriter<score::Event>,
 ) {
-    let ball_tf = ball_query.single();
+    let mut in_play = ball_query.iter().len();
+
+    for (ball, ball_tf, mut vel, mut visibility, last_hit) in &mut ball_query {
+        let Some((_, bb)) = score_zones
+            .iter()
+            .find(|(tf, _)| is_inside_bounds(tf, ball_tf))
+        else {
+            continue;
+        };
 
-    for (tf, bb) in &score_zones {
-        if is_inside_bounds(tf, ball_tf) {
-            ev_score.send(score::Event::new(bb.side.opposite()));
+        if rules.point_ends == PointEnds::FirstBall || in_play == 1 {
+            let scorer = last_hit
+                .side
+                .filter(|&side| side != bb.side)
+                .unwrap_or_else(|| bb.side.opposite());
+            ev_score.send(score::Event::new(scorer).conceded_by(bb.side));
+            return;
         }
+
+        in_play -= 1;
+        *vel = Velocity::default();
+        *visibility = Visibility::Hidden;
+        commands.entity(ball).insert(Out);
     }
 }
 
-/// Handles score events by resetting the ball and the players' positions. The
-/// player that scored has their score incremented.
+/// Handles score events by resetting the balls and the players' positions and
+/// lining up the next serve. Balls put into play by power-ups are taken off
+/// the court. The player that scored has their score incremented, or when
+/// playing for elimination, the player that conceded loses a life. A player
+/// with no lives left is knocked out: their paddles are taken off the court
+/// and their goal is walled up.
 #[allow(clippy::type_complexity)]
 pub fn handle_score_event(
+    mut commands: Commands,
     mut ev_score: EventReader<score::Event>,
+    mut serve: ResMut<Serve>,
+    rules: Res<MatchRules>,
     mut set: ParamSet<(
-        Query<(&mut Transform, &Velocity), With<Ball>>,
-        Query<(&mut Transform, &Player)>,
+        Query<
+            (
+                Entity,
+                &mut Transform,
+                &mut Velocity,
+                &mut Spin,
+                &mut LastHit,
+                &mut Visibility,
+                &StartingPosition,
+                Option<&Extra>,
+            ),
+            With<Ball>,
+        >,
+        Query<(Entity, &mut Transform, &Player)>,
         Query<(&mut Score, &mut Text)>,
+        Query<(Entity, &BoundingBox), With<bounding_box::ScoreDetector>>,
     )>,
 ) {
     if let Some(ev) = ev_score.iter().next() {
         info!("Scored {:?}", ev);
 
-        // Reset ball position
-        set.p0().get_single_mut().unwrap().0.translation =
-            (BALL_DEFAULT_STARTING_POSITION, 0.0).into();
+        // Grant a point to the player that scored, or take a life from the
+        // player that conceded
+        let (side, eliminating) = match rules.victory {
+            Victory::Points => (ev.player_side, false),
+            Victory::Elimination => (ev.conceded, true),
+        };
+        let mut knocked_out = None;
+        if let Some((mut score, mut text)) = set.p2().iter_mut().find(|(s, _)| s.side == side) {
+            if eliminating {
+                score.decrement();
+                if score.value == 0 {
+                    knocked_out = Some(side);
+                }
+            } else {
+                score.increment();
+            }
+            text.sections[0].value = score.to_string();
+        }
+
+        if let Some(side) = knocked_out {
+            info!("{:?} is knocked out", side);
+            for (goal, _) in set.p3().iter().filter(|(_, bb)| bb.side == side) {
+                commands
+                    .entity(goal)
+                    .remove::<bounding_box::ScoreDetector>();
+            }
+        }
 
-        // Reset paddle positions
-        for (mut tf, player) in set.p1().iter_mut() {
+        // Reset paddle positions, taking the knocked out player's off the
+        // court
+        let mut standing = Vec::new();
+        for (paddle, mut tf, player) in set.p1().iter_mut() {
+            if knocked_out == Some(player.side) {
+                commands.entity(paddle).despawn_recursive();
+                continue;
+            }
             tf.translation = (player.starting_pos, 0.0).into();
+            standing.push(player.side);
         }
 
-        // Grant a point to the player that scored
-        if let Some((mut score, mut text)) =
-            set.p2().iter_mut().find(|(s, _)| s.side == ev.player_side)
+        // Hold the balls where they started until they are served again,
+        // toward someone still playing
+        let mut toward = serve.next(ev.conceded);
+        if !standing.is_empty() && !standing.contains(&toward) {
+            toward = if standing.contains(&ev.player_side) {
+                ev.player_side
+            } else {
+                standing[0]
+            };
+        }
+        for (
+            ball,
+            mut ball_tf,
+            mut ball_vel,
+            mut spin,
+            mut last_hit,
+            mut visibility,
+            start,
+            extra,
+        ) in &mut set.p0()
         {
-            score.increment();
-            text.sections[0].value = score.to_string();
+            if extra.is_some() {
+                commands.entity(ball).despawn_recursive();
+                continue;
+            }
+            ball_tf.translation = (start.0, 0.0).into();
+            ball_tf.rotation = Quat::IDENTITY;
+            *ball_vel = Velocity::default();
+            *spin = Spin::default();
+            *last_hit = LastHit::default();
+            *visibility = Visibility::Inherited;
+            commands
+                .entity(ball)
+                .insert(Serving::new(toward, serve.countdown))
+                .remove::<(Stuck, Out)>();
         }
     }
 
     ev_score.clear();
 }
 
-/// Plays a sound when a collision occurs.
+/// Keeps the match stats up to date with the current rally.
+pub fn record_match_stats(
+    mut ev_score: EventReader<score::Event>,
+    rally: Res<RallySpeed>,
+    mut stats: ResMut<MatchStats>,
+) {
+    stats.points_played += ev_score.iter().count() as u32;
+    stats.record_rally(&rally);
+}
+
+/// Starts a new rally after a point is scored, so the next serve goes out at
+/// the serving speed.
+pub fn reset_rally(mut ev_score: EventReader<score::Event>, mut rally: ResMut<RallySpeed>) {
+    if ev_score.iter().next().is_none() {
+        return;
+    }
+    ev_score.clear();
+
+    rally.reset();
+}
+
+/// Counts down to the serve, then launches the ball toward the receiving side
+/// at a random angle. The serving side can serve early with the serve action.
+pub fn serve_ball(
+    mut commands: Commands,
+    mut ball_q: Query<(Entity, &mut Serving, &mut Velocity), With<Ball>>,
+    paddle_q: Query<(&Player, &ActionState)>,
+    fixed_time: Res<FixedTime>,
+    serve: Res<Serve>,
+    rally: Res<RallySpeed>,
+) {
+    for (ball, mut serving, mut vel) in &mut ball_q {
+        let served_early = paddle_q.iter().any(|(player, actions)| {
+            player.side == serving.toward.opposite() && actions.pressed(Action::Serve)
+        });
+        if serving.tick(fixed_time.period) || served_early {
+            *vel = (serve.direction(serving.toward) * rally.speed).into();
+            commands.entity(ball).remove::<Serving>();
+        }
+    }
+}
+
+/// Shows the countdown to the next serve while the balls are waiting.
+pub fn show_countdown(
+    ball_q: Query<&Serving, With<Ball>>,
+    mut text_q: Query<&mut Text, With<CountdownText>>,
+) {
+    let countdown = ball_q
+        .iter()
+        .next()
+        .map(|serving| serving.seconds_left().to_string())
+        .unwrap_or_default();
+    for mut text in &mut text_q {
+        if text.sections[0].value != countdown {
+            text.sections[0].value = countdown.clone();
+        }
+    }
+}
+
+/// Plays a sound when the ball hits a paddle, an obstacle or a brick. Bounces
+/// off the walls are silent.
 pub fn collision_sound(
     mut ev_collision: EventReader<collider::Event>,
+    wall_q: Query<(), With<Wall>>,
     asset_server: Res<AssetServer>,
     audio: Res<Audio>,
+    settings: Res<Settings>,
 ) {
-    for e in ev_collision.iter() {
-        if e.kind != Collision::Inside {
-            let sound = asset_server.load("sound/collision.ogg");
-            audio.play(sound);
+    for ev in ev_collision.iter() {
+        if ev.struck.is_some_and(|struck| wall_q.contains(struck)) {
+            continue;
         }
+        let sound = asset_server.load("sound/collision.ogg");
+        audio.play_with_settings(
+            sound,
+            PlaybackSettings::default().with_volume(settings.audio.effects_volume),
+        );
     }
 }
 
@@ -308,9 +790,11 @@ pub fn start_background_music(
     asset_server: Res<AssetServer>,
     audio: Res<Audio>,
     audio_sinks: Res<Assets<AudioSink>>,
+    settings: Res<Settings>,
 ) {
     let music = asset_server.load("sound/bgm.ogg");
-    let handle = audio_sinks.get_handle(audio.play(music));
+    let playback = PlaybackSettings::default().with_volume(settings.audio.music_volume);
+    let handle = audio_sinks.get_handle(audio.play_with_settings(music, playback));
     commands.insert_resource(MusicController(handle));
 }
 
@@ -323,15 +807,52 @@ pub fn stop_background_music(
     }
 }
 
+/// Pauses the background music, so it can pick up where it left off.
+pub fn pause_background_music(
+    audio_sinks: Res<Assets<AudioSink>>,
+    music_controller: Res<MusicController>,
+) {
+    if let Some(sink) = audio_sinks.get(&music_controller.0) {
+        sink.pause();
+    }
+}
+
+/// Resumes the background music after it was paused.
+pub fn resume_background_music(
+    audio_sinks: Res<Assets<AudioSink>>,
+    music_controller: Res<MusicController>,
+) {
+    if let Some(sink) = audio_sinks.get(&music_controller.0) {
+        sink.play();
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use bevy::prelude::{
+        App, CoreSchedule, Entity, EventReader, Input, IntoSystemAppConfig, IntoSystemAppConfigs,
+        IntoSystemConfig, KeyCode, MinimalPlugins, Query, ResMut, Resource, State, Transform, Vec2,
+        Vec3, With,
+    };
+
     use crate::{
         component::{
-            ball,
-            paddle::{self, Side},
-            Bundle,
+            ai::{predict_intercept, Difficulty},
+            ball::{self, Ball},
+            collider,
+            obstacle::Shape,
+            paddle::{self, Player, Role, Side},
+            score::{self as score_counter, Score},
+            serve::CountdownBundle,
+            wall, Bundle,
         },
-        tests::helpers::{default_setup_graphics, Test},
+        constants::{
+            BALL_SCALE, BOTTOM_WALL_POSITION, BOTTOM_WALL_SIZE, DEFAULT_BALL_SPEED,
+            PADDLE_SPEED_MULTIPLIER, TIME_STEP, TOP_WALL_POSITION, TOP_WALL_SIZE,
+        },
+        resources::{MatchResult, MatchRules, Opponent, PowerUps, Series},
+        states::AppState,
+        tests::helpers::{default_setup_graphics, run_at_fps, steady_rally, still_obstacle, Test},
     };
 
     #[test]
@@ -342,7 +863,8 @@ mod test {
             setup: |app| {
                 app.add_event::<collider::Event>()
                     .add_event::<shake::Event>()
-                    .add_system(move_ball)
+                    .init_resource::<Deflection>()
+                    .init_resource::<RallySpeed>()
                     .add_system(collide_ball);
                 app.world
                     .spawn(Bundle::default().with_position(Vec2::new(10.0, 0.0)));
@@ -367,70 +889,1090 @@ mod test {
     }
 
     #[test]
-    fn apply_velocity_to_paddles_test() {
+    fn ball_is_deflected_by_where_it_hits_the_paddle() {
         use super::*;
 
         Test {
             setup: |app| {
-                app.add_system(move_paddles);
-                let paddle_bundle = paddle::Bundle {
-                    velocity: Vec2::new(5.0, 0.0).into(),
-                    ..Default::default()
-                };
-                app.world.spawn(paddle_bundle).id()
+                app.add_event::<collider::Event>()
+                    .add_event::<shake::Event>()
+                    .init_resource::<Deflection>()
+                    .insert_resource(steady_rally())
+                    .add_system(collide_ball);
+                app.world
+                    .spawn(Bundle::default().with_position(Vec2::new(100.0, 0.0)));
+                // Heading straight for the top half of the paddle
+                app.world
+                    .spawn(
+                        ball::Bundle::default()
+                            .with_position(Vec2::new(70.0, 30.0))
+                            .with_velocity(Vec2::new(DEFAULT_BALL_SPEED, 0.0)),
+                    )
+                    .id()
             },
             setup_graphics: default_setup_graphics,
-            frames: 5,
-            check: |app, paddle_id| {
-                let paddle_tf = app.world.get::<Transform>(paddle_id).unwrap();
+            frames: 60,
+            check: |app, ball_id| {
+                let ball_vel = app.world.get::<Velocity>(ball_id).unwrap();
 
-                // y and z should be unchanged
-                assert_eq!(paddle_tf.translation.y, 0.0);
-                assert_eq!(paddle_tf.translation.z, 0.0);
+                // Sent back up and away, at the same speed
+                assert!(ball_vel.x < 0.0);
+                assert!(ball_vel.y > 0.0);
+                assert!((ball_vel.length() - DEFAULT_BALL_SPEED).abs() < 1e-3);
+            },
+        }
+        .run();
+    }
 
-                // x should be positive, because the paddle should have moved to the right
-                assert!(paddle_tf.translation.x > 0.0);
+    #[test]
+    fn moving_paddle_puts_spin_on_ball() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_event::<collider::Event>()
+                    .add_event::<shake::Event>()
+                    .insert_resource(Deflection::default().with_paddle_velocity_transfer(0.0))
+                    .insert_resource(RallySpeed::default().with_hit_multiplier(1.0))
+                    .add_system(apply_spin.before(collide_ball))
+                    .add_system(collide_ball);
+                app.world.spawn(paddle::Bundle {
+                    velocity: Vec2::new(0.0, PADDLE_SPEED_MULTIPLIER).into(),
+                    ..Bundle::default().with_position(Vec2::new(100.0, 0.0))
+                });
+                app.world
+                    .spawn(
+                        ball::Bundle::default()
+                            .with_position(Vec2::new(70.0, 0.0))
+                            .with_velocity(Vec2::new(DEFAULT_BALL_SPEED, 0.0)),
+                    )
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 60,
+            check: |app, ball_id| {
+                let ball_tf = app.world.get::<Transform>(ball_id).unwrap();
+                let ball_vel = app.world.get::<Velocity>(ball_id).unwrap();
+                let spin = app.world.get::<Spin>(ball_id).unwrap();
+
+                // The paddle swept up across the ball's right side, spinning
+                // it counterclockwise, which curves it downwards as it heads
+                // back left
+                assert!(**spin > 0.0);
+                assert!(ball_vel.x < 0.0);
+                assert!(ball_vel.y < 0.0);
+                assert_ne!(ball_tf.rotation, Transform::default().rotation);
             },
         }
         .run();
     }
 
     #[test]
-    fn paddles_cant_escape_bounding_box() {
+    fn fast_ball_does_not_tunnel_through_paddle() {
         use super::*;
 
         Test {
             setup: |app| {
-                app.add_system(move_paddles);
+                app.add_event::<collider::Event>()
+                    .add_event::<shake::Event>()
+                    .init_resource::<Deflection>()
+                    .insert_resource(steady_rally())
+                    .add_system(collide_ball);
+                app.world
+                    .spawn(Bundle::default().with_position(Vec2::new(100.0, 0.0)));
+                // Moves 100 units per step, which is far more than the paddle
+                // is wide
+                app.world
+                    .spawn(
+                        ball::Bundle::default()
+                            .with_position(Vec2::new(-30.0, 0.0))
+                            .with_velocity(Vec2::new(DEFAULT_BALL_SPEED * 120.0, 0.0)),
+                    )
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 4,
+            check: |app, ball_id| {
+                let ball_tf = app.world.get::<Transform>(ball_id).unwrap();
+                let ball_vel = app.world.get::<Velocity>(ball_id).unwrap();
+
+                assert!(ball_vel.x < 0.0);
+                assert!(ball_tf.translation.x < -30.0);
+                assert_eq!(ball_tf.translation.y, 0.0);
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn fast_ball_does_not_tunnel_through_wall() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_event::<collider::Event>()
+                    .add_event::<shake::Event>()
+                    .init_resource::<Deflection>()
+                    .init_resource::<RallySpeed>()
+                    .add_system(collide_ball);
                 app.world.spawn(
-                    bounding_box::Bundle::default()
-                        .with_dimensions(100.0, 100.0)
-                        .on_side(Side::Left),
+                    wall::Bundle::default()
+                        .with_size(TOP_WALL_SIZE.x, TOP_WALL_SIZE.y)
+                        .at(TOP_WALL_POSITION),
                 );
                 app.world
                     .spawn(
-                        paddle::Bundle {
-                            velocity: Vec2::new(50.0, 0.0).into(),
-                            ..Default::default()
-                        }
-                        .with_dimensions(1.0, 1.0),
+                        ball::Bundle::default()
+                            .with_position(Vec2::new(0.0, 200.0))
+                            .with_velocity(Vec2::new(600.0, DEFAULT_BALL_SPEED * 100.0)),
                     )
                     .id()
             },
             setup_graphics: default_setup_graphics,
-            frames: 600,
-            check: |app, paddle_id| {
-                let paddle_tf = app.world.get::<Transform>(paddle_id).unwrap();
+            frames: 1,
+            check: |app, ball_id| {
+                let ball_tf = app.world.get::<Transform>(ball_id).unwrap();
+                let ball_vel = app.world.get::<Velocity>(ball_id).unwrap();
 
-                // y and z should be unchanged
-                assert_eq!(paddle_tf.translation.y, 0.0);
-                assert_eq!(paddle_tf.translation.z, 0.0);
+                // Bounced off the underside of the wall and kept going
+                assert_eq!(**ball_vel, Vec2::new(600.0, -DEFAULT_BALL_SPEED * 100.0));
+                assert!(ball_tf.translation.y < 200.0);
+                assert!(ball_tf.translation.x > 0.0);
+            },
+        }
+        .run();
+    }
 
-                // x should be no greater than 50.0, because the paddle should
-                // have hit the bounding box
-                assert!(paddle_tf.translation.x < 50.0);
+    #[test]
+    fn fast_ball_stops_inside_score_zone() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_event::<collider::Event>()
+                    .add_event::<shake::Event>()
+                    .init_resource::<Deflection>()
+                    .init_resource::<RallySpeed>()
+                    .add_system(collide_ball);
+                app.world.spawn((
+                    bounding_box::Bundle::default()
+                        .with_dimensions(25.0, 500.0)
+                        .with_position(Vec2::new(250.0, 0.0))
+                        .on_side(Side::Right),
+                    Collider,
+                    bounding_box::ScoreDetector,
+                ));
+                app.world
+                    .spawn(
+                        ball::Bundle::default()
+                            .with_position(Vec2::new(200.0, 0.0))
+                            .with_velocity(Vec2::new(DEFAULT_BALL_SPEED * 120.0, 0.0)),
+                    )
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 1,
+            check: |app, ball_id| {
+                let ball_tf = app.world.get::<Transform>(ball_id).unwrap();
+                let zone_tf =
+                    Transform::from_xyz(250.0, 0.0, 0.0).with_scale((25.0, 500.0, 0.0).into());
+
+                assert!(is_inside_bounds(&zone_tf, ball_tf));
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn rally_speeds_up_on_paddle_hits_until_the_cap() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_event::<collider::Event>()
+                    .add_event::<shake::Event>()
+                    .init_resource::<Deflection>()
+                    .insert_resource(
+                        RallySpeed::default()
+                            .with_serve_speed(600.0)
+                            .with_hit_multiplier(1.5)
+                            .with_max_speed(1200.0),
+                    )
+                    .add_system(collide_ball);
+                app.world
+                    .spawn(Bundle::default().with_position(Vec2::new(-100.0, 0.0)));
+                app.world
+                    .spawn(Bundle::default().with_position(Vec2::new(100.0, 0.0)));
+                app.world
+                    .spawn(ball::Bundle::default().with_velocity(Vec2::new(600.0, 0.0)))
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 60,
+            check: |app, ball_id| {
+                let ball_vel = app.world.get::<Velocity>(ball_id).unwrap();
+                let rally = app.world.resource::<RallySpeed>();
+
+                // 600 -> 900 -> 1200, and no faster after that
+                assert!(rally.hits > 3);
+                assert_eq!(rally.speed, 1200.0);
+                assert!((ball_vel.length() - 1200.0).abs() < 1e-3);
             },
         }
         .run();
     }
+
+    #[test]
+    fn rally_resets_when_a_point_is_scored() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_event::<score::Event>()
+                    .insert_resource(RallySpeed {
+                        speed: 400.0,
+                        hits: 7,
+                        ..RallySpeed::default()
+                    })
+                    .add_system(reset_rally);
+                app.world.send_event(score::Event::new(Side::Left));
+                app.world.spawn_empty().id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 1,
+            check: |app, _| {
+                let rally = app.world.resource::<RallySpeed>();
+
+                assert_eq!(rally.hits, 0);
+                assert_eq!(rally.speed, DEFAULT_BALL_SPEED);
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn ball_is_served_toward_the_receiver_after_the_countdown() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.init_resource::<Serve>()
+                    .init_resource::<RallySpeed>()
+                    .add_system(serve_ball);
+                app.world
+                    .spawn((ball::Bundle::default(), Serving::new(Side::Left, 0.5)))
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 40,
+            check: |app, ball_id| {
+                let ball_vel = app.world.get::<Velocity>(ball_id).unwrap();
+
+                assert!(app.world.get::<Serving>(ball_id).is_none());
+                assert!(ball_vel.x < 0.0);
+                assert!((ball_vel.length() - DEFAULT_BALL_SPEED).abs() < 1e-3);
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn countdown_is_shown_while_the_ball_waits() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.init_resource::<Serve>()
+                    .init_resource::<RallySpeed>()
+                    .add_system(serve_ball)
+                    .add_system(show_countdown.after(serve_ball));
+                app.world
+                    .spawn((ball::Bundle::default(), Serving::new(Side::Right, 3.0)));
+                app.world.spawn(CountdownBundle::default()).id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 10,
+            check: |app, text_id| {
+                let text = app.world.get::<Text>(text_id).unwrap();
+
+                assert_eq!(text.sections[0].value, "3");
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn game_win_counts_toward_the_series_once() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_state::<AppState>()
+                    .insert_resource(MatchRules::default().with_games_in_series(3))
+                    .init_resource::<Series>()
+                    .init_resource::<Opponent>()
+                    .add_system(detect_win_condition);
+                app.world.spawn(Score::new(2, Side::Left));
+                app.world.spawn(Score::new(5, Side::Right)).id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 5,
+            check: |app, _| {
+                let series = app.world.resource::<Series>();
+
+                // One game down, so the next one starts
+                assert_eq!(
+                    *series,
+                    Series {
+                        left: 0,
+                        right: 1,
+                        ..Default::default()
+                    }
+                );
+                assert_eq!(app.world.resource::<State<AppState>>().0, AppState::InGame);
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn series_win_shows_the_results() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_state::<AppState>()
+                    .insert_resource(MatchRules::default().with_games_in_series(3))
+                    .insert_resource(Series {
+                        left: 1,
+                        right: 0,
+                        ..Default::default()
+                    })
+                    .init_resource::<Opponent>()
+                    .add_system(detect_win_condition);
+                app.world.spawn(Score::new(6, Side::Left));
+                app.world.spawn(Score::new(4, Side::Right)).id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 2,
+            check: |app, _| {
+                let result = app.world.resource::<MatchResult>();
+
+                assert_eq!(
+                    *result,
+                    MatchResult {
+                        winner: Side::Left,
+                        scores: vec![(Side::Left, 6), (Side::Right, 4)],
+                    }
+                );
+                assert_eq!(
+                    *app.world.resource::<Series>(),
+                    Series {
+                        left: 2,
+                        right: 0,
+                        ..Default::default()
+                    }
+                );
+                assert_eq!(
+                    app.world.resource::<State<AppState>>().0,
+                    AppState::GameOver
+                );
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn ai_moves_its_paddle_to_meet_the_ball() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_systems((ai_input, paddle_input, move_paddles).chain());
+                let paddle = app
+                    .world
+                    .spawn((
+                        Bundle::default().with_position(Vec2::new(100.0, 0.0)),
+                        Ai::new(Difficulty {
+                            error: 0.0,
+                            ..Difficulty::HARD
+                        }),
+                    ))
+                    .id();
+                app.world
+                    .spawn(ball::Bundle::default().with_velocity(Vec2::new(50.0, 25.0)));
+                paddle
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 60,
+            check: |app, paddle_id| {
+                let paddle_tf = app.world.get::<Transform>(paddle_id).unwrap();
+
+                // Headed for where the ball will cross, at y = 50
+                assert!(paddle_tf.translation.y > 25.0);
+                assert!(paddle_tf.translation.y <= 50.0);
+            },
+        }
+        .run();
+    }
+
+    /// The x coordinate the ball's crossing is predicted and recorded at.
+    const CROSSING_X: f32 = 200.0;
+
+    /// Where the ball first crossed `CROSSING_X`, found by interpolating
+    /// between the steps either side of it.
+    #[derive(Resource, Default)]
+    struct Crossing {
+        previous: Option<Vec2>,
+        at: Option<f32>,
+    }
+
+    fn record_crossing(ball_q: Query<&Transform, With<Ball>>, mut crossing: ResMut<Crossing>) {
+        let pos = ball_q.single().translation.truncate();
+        if let (Some(prev), None) = (crossing.previous, crossing.at) {
+            if prev.x < CROSSING_X && pos.x >= CROSSING_X {
+                let t = (CROSSING_X - prev.x) / (pos.x - prev.x);
+                crossing.at = Some((pos.y - prev.y).mul_add(t, prev.y));
+            }
+        }
+        crossing.previous = Some(pos);
+    }
+
+    /// The walls and the ball's starting point and velocity for the
+    /// prediction test. The ball bounces off the top wall and then the bottom
+    /// one before it gets to the line.
+    fn prediction_setup() -> (Transform, Transform, Transform, Vec2) {
+        let top = Transform::from_translation(TOP_WALL_POSITION.extend(0.0))
+            .with_scale(TOP_WALL_SIZE.extend(0.0));
+        let bottom = Transform::from_translation(BOTTOM_WALL_POSITION.extend(0.0))
+            .with_scale(BOTTOM_WALL_SIZE.extend(0.0));
+        let ball = Transform::from_xyz(-200.0, 10.0, 0.0).with_scale(BALL_SCALE);
+        (top, bottom, ball, Vec2::new(300.0, 600.0))
+    }
+
+    #[test]
+    fn predicted_intercept_matches_the_simulation() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                let (_, _, ball_tf, ball_vel) = prediction_setup();
+                app.add_event::<collider::Event>()
+                    .add_event::<shake::Event>()
+                    .init_resource::<Deflection>()
+                    .init_resource::<RallySpeed>()
+                    .init_resource::<Crossing>()
+                    .add_system(collide_ball)
+                    .add_system(record_crossing.after(collide_ball));
+                app.world.spawn(
+                    wall::Bundle::default()
+                        .with_size(TOP_WALL_SIZE.x, TOP_WALL_SIZE.y)
+                        .at(TOP_WALL_POSITION),
+                );
+                app.world.spawn(
+                    wall::Bundle::default()
+                        .with_size(BOTTOM_WALL_SIZE.x, BOTTOM_WALL_SIZE.y)
+                        .at(BOTTOM_WALL_POSITION),
+                );
+                app.world
+                    .spawn(
+                        ball::Bundle::default()
+                            .with_position(ball_tf.translation.truncate())
+                            .with_velocity(ball_vel),
+                    )
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 100,
+            check: |app, _| {
+                let (top, bottom, ball_tf, ball_vel) = prediction_setup();
+                let predicted = predict_intercept(&ball_tf, ball_vel, CROSSING_X, &[top, bottom])
+                    .expect("the ball is heading for the line");
+                let actual = app
+                    .world
+                    .resource::<Crossing>()
+                    .at
+                    .expect("the ball reached the line");
+
+                assert!((predicted - actual).abs() < 1.0, "{predicted} != {actual}");
+            },
+        }
+        .run();
+    }
+
+    /// Counts how many collision events have been sent.
+    #[derive(Resource, Default)]
+    struct CollisionCount(usize);
+
+    fn count_collisions(
+        mut ev_collision: EventReader<collider::Event>,
+        mut count: ResMut<CollisionCount>,
+    ) {
+        count.0 += ev_collision.iter().count();
+    }
+
+    #[test]
+    fn ball_is_pushed_out_of_paddle_and_bounces_once() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_event::<collider::Event>()
+                    .add_event::<shake::Event>()
+                    .init_resource::<Deflection>()
+                    .insert_resource(steady_rally())
+                    .init_resource::<CollisionCount>()
+                    .add_system(collide_ball)
+                    .add_system(count_collisions.after(collide_ball));
+                app.world
+                    .spawn(Bundle::default().with_position(Vec2::new(10.0, 0.0)));
+                // Deep inside the paddle, and slow enough that a plain
+                // reflection would leave it inside for several steps
+                app.world
+                    .spawn(
+                        ball::Bundle::default()
+                            .with_position(Vec2::new(5.0, 0.0))
+                            .with_velocity(Vec2::new(30.0, 0.0)),
+                    )
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 10,
+            check: |app, ball_id| {
+                let ball_tf = app.world.get::<Transform>(ball_id).unwrap();
+                let ball_vel = app.world.get::<Velocity>(ball_id).unwrap();
+
+                // Pushed out through the nearest (left) side of the paddle and
+                // bounced back exactly once
+                assert!(ball_tf.translation.x <= -7.5);
+                assert_eq!(ball_tf.translation.y, 0.0);
+                assert_eq!(**ball_vel, Vec2::new(-30.0, 0.0));
+                assert_eq!(app.world.resource::<CollisionCount>().0, 1);
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn ball_leaving_paddle_is_not_reflected() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_event::<collider::Event>()
+                    .add_event::<shake::Event>()
+                    .init_resource::<Deflection>()
+                    .init_resource::<RallySpeed>()
+                    .init_resource::<CollisionCount>()
+                    .add_system(collide_ball)
+                    .add_system(count_collisions.after(collide_ball));
+                app.world
+                    .spawn(Bundle::default().with_position(Vec2::new(10.0, 0.0)));
+                // Overlapping the paddle, but already moving away from it
+                app.world
+                    .spawn(
+                        ball::Bundle::default()
+                            .with_position(Vec2::new(0.0, 0.0))
+                            .with_velocity(Vec2::new(-30.0, 0.0)),
+                    )
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 5,
+            check: |app, ball_id| {
+                let ball_tf = app.world.get::<Transform>(ball_id).unwrap();
+                let ball_vel = app.world.get::<Velocity>(ball_id).unwrap();
+
+                assert!(ball_tf.translation.x < -7.5);
+                assert_eq!(**ball_vel, Vec2::new(-30.0, 0.0));
+                assert_eq!(app.world.resource::<CollisionCount>().0, 0);
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn apply_velocity_to_paddles_test() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_system(move_paddles);
+                let paddle_bundle = paddle::Bundle {
+                    velocity: Vec2::new(5.0, 0.0).into(),
+                    ..Default::default()
+                };
+                app.world.spawn(paddle_bundle).id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 5,
+            check: |app, paddle_id| {
+                let paddle_tf = app.world.get::<Transform>(paddle_id).unwrap();
+
+                // y and z should be unchanged
+                assert_eq!(paddle_tf.translation.y, 0.0);
+                assert_eq!(paddle_tf.translation.z, 0.0);
+
+                // x should be positive, because the paddle should have moved to the right
+                assert!(paddle_tf.translation.x > 0.0);
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn paddles_cant_escape_bounding_box() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_system(move_paddles);
+                app.world.spawn(
+                    bounding_box::Bundle::default()
+                        .with_dimensions(100.0, 100.0)
+                        .on_side(Side::Left),
+                );
+                app.world
+                    .spawn(
+                        paddle::Bundle {
+                            velocity: Vec2::new(50.0, 0.0).into(),
+                            ..Default::default()
+                        }
+                        .with_dimensions(1.0, 1.0),
+                    )
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 600,
+            check: |app, paddle_id| {
+                let paddle_tf = app.world.get::<Transform>(paddle_id).unwrap();
+
+                // y and z should be unchanged
+                assert_eq!(paddle_tf.translation.y, 0.0);
+                assert_eq!(paddle_tf.translation.z, 0.0);
+
+                // x should be no greater than 50.0, because the paddle should
+                // have hit the bounding box
+                assert!(paddle_tf.translation.x < 50.0);
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn teammates_keep_to_their_own_zones() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_system(move_paddles);
+                // A defender at the back and an attacker in front of it
+                for (role, x) in [(Role::Defender, -100.0), (Role::Attacker, 0.0)] {
+                    app.world.spawn(
+                        bounding_box::Bundle::default()
+                            .with_dimensions(100.0, 100.0)
+                            .with_position(Vec2::new(x, 0.0))
+                            .on_side(Side::Left)
+                            .for_role(role),
+                    );
+                }
+                app.world.spawn(
+                    paddle::Bundle {
+                        velocity: Vec2::new(50.0, 0.0).into(),
+                        ..Default::default()
+                    }
+                    .with_dimensions(1.0, 1.0)
+                    .with_position(Vec2::new(-100.0, 0.0)),
+                );
+                app.world
+                    .spawn(
+                        paddle::Bundle {
+                            velocity: Vec2::new(-50.0, 0.0).into(),
+                            ..Default::default()
+                        }
+                        .with_role(Role::Attacker)
+                        .with_dimensions(1.0, 1.0),
+                    )
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 600,
+            check: |app, attacker| {
+                for e in app.world.iter_entities() {
+                    let (Some(player), Some(tf)) = (e.get::<Player>(), e.get::<Transform>()) else {
+                        continue;
+                    };
+                    match player.role {
+                        // Neither can cross into the other's zone
+                        Role::Defender => assert!(tf.translation.x < -50.0),
+                        Role::Attacker => {
+                            assert_eq!(e.id(), attacker);
+                            assert!(tf.translation.x > -50.0);
+                        }
+                    }
+                }
+            },
+        }
+        .run();
+    }
+
+    /// Ball and paddle positions after each fixed step.
+    #[derive(Resource, Default)]
+    struct Trajectory(Vec<(Vec3, Vec3)>);
+
+    fn record_trajectory(
+        mut trajectory: ResMut<Trajectory>,
+        ball_q: Query<&Transform, With<Ball>>,
+        paddle_q: Query<&Transform, With<Player>>,
+    ) {
+        trajectory
+            .0
+            .push((ball_q.single().translation, paddle_q.single().translation));
+    }
+
+    fn simulate_at_fps(fps: u32) -> Vec<(Vec3, Vec3)> {
+        use super::*;
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_state::<AppState>()
+            .add_event::<collider::Event>()
+            .add_event::<shake::Event>()
+            .add_event::<score::Event>()
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<Trajectory>()
+            .init_resource::<Deflection>()
+            .init_resource::<RallySpeed>()
+            .init_resource::<Serve>()
+            .init_resource::<MatchRules>()
+            .init_resource::<Series>()
+            .init_resource::<MatchStats>()
+            .init_resource::<PowerUps>()
+            .init_resource::<Opponent>()
+            .insert_resource(FixedTime::new_from_secs(TIME_STEP))
+            .add_systems(simulation_systems().in_schedule(CoreSchedule::FixedUpdate))
+            .add_system(
+                record_trajectory
+                    .after(detect_win_condition)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+
+        app.world.spawn(
+            wall::Bundle::default()
+                .with_size(TOP_WALL_SIZE.x, TOP_WALL_SIZE.y)
+                .at(TOP_WALL_POSITION),
+        );
+        app.world.spawn(
+            wall::Bundle::default()
+                .with_size(BOTTOM_WALL_SIZE.x, BOTTOM_WALL_SIZE.y)
+                .at(BOTTOM_WALL_POSITION),
+        );
+        app.world.spawn(paddle::Bundle {
+            velocity: Vec2::new(0.0, 40.0).into(),
+            ..Bundle::default().with_position(Vec2::new(100.0, 0.0))
+        });
+        app.world
+            .spawn(ball::Bundle::default().with_velocity(Vec2::new(120.0, 80.0)));
+
+        run_at_fps(&mut app, fps, 3);
+
+        std::mem::take(&mut app.world.resource_mut::<Trajectory>().0)
+    }
+
+    #[test]
+    fn simulation_is_frame_rate_independent() {
+        let at_60 = simulate_at_fps(60);
+        assert!(!at_60.is_empty());
+
+        // The ball should have bounced off the paddle and moved back left
+        let (ball_end, _) = at_60.last().unwrap();
+        assert!(ball_end.x < 100.0);
+
+        assert_eq!(simulate_at_fps(30), at_60);
+        assert_eq!(simulate_at_fps(144), at_60);
+    }
+
+    #[test]
+    fn ball_bounces_off_round_obstacles() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_event::<collider::Event>()
+                    .add_event::<shake::Event>()
+                    .init_resource::<Deflection>()
+                    .insert_resource(steady_rally())
+                    .add_system(collide_ball);
+                app.world
+                    .spawn(still_obstacle(Shape::Circle(20.0), Vec2::new(60.0, 0.0)).bundle());
+                app.world
+                    .spawn(ball::Bundle::default().with_velocity(Vec2::new(600.0, 0.0)))
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 10,
+            check: |app, ball_id| {
+                let ball_tf = app.world.get::<Transform>(ball_id).unwrap();
+                let ball_vel = app.world.get::<Velocity>(ball_id).unwrap();
+
+                // Straight back the way it came, without passing into the obstacle
+                assert!(ball_vel.x < 0.0);
+                assert_eq!(ball_vel.y, 0.0);
+                assert!(ball_tf.translation.x < 60.0 - 20.0 - BALL_SCALE.x / 2.0);
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn reversed_paddles_move_the_other_way() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_system(paddle_input);
+                let mut actions = ActionState::default();
+                actions.press(Action::MoveUp);
+                app.world
+                    .spawn((Bundle::left_player(), Reversed))
+                    .insert(actions)
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 1,
+            check: |app, paddle| {
+                assert_eq!(
+                    **app.world.get::<Velocity>(paddle).unwrap(),
+                    Vec2::NEG_Y * PADDLE_SPEED_MULTIPLIER
+                );
+            },
+        }
+        .run();
+    }
+
+    /// An app that scores points, with the left goal at x = -100 and two
+    /// balls in the middle of the court.
+    fn scoring_app(rules: MatchRules) -> (App, Entity, Entity) {
+        use super::*;
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<score::Event>()
+            .init_resource::<Serve>()
+            .insert_resource(rules)
+            .add_systems((detect_score, handle_score_event).chain());
+
+        app.world.spawn((
+            bounding_box::Bundle::default()
+                .with_dimensions(20.0, 100.0)
+                .with_position(Vec2::new(-100.0, 0.0))
+                .on_side(Side::Left),
+            bounding_box::ScoreDetector,
+        ));
+        app.world
+            .spawn(score_counter::Bundle::default().side(Side::Right));
+        let first = app
+            .world
+            .spawn(ball::Bundle::default().with_position(Vec2::new(0.0, 20.0)))
+            .id();
+        let second = app
+            .world
+            .spawn(ball::Bundle::default().with_position(Vec2::new(0.0, -20.0)))
+            .id();
+        (app, first, second)
+    }
+
+    fn right_score(app: &mut App) -> u64 {
+        app.world
+            .query::<&Score>()
+            .iter(&app.world)
+            .find(|score| score.side == Side::Right)
+            .unwrap()
+            .value
+    }
+
+    fn move_ball(app: &mut App, ball: Entity, to: Vec2) {
+        app.world.get_mut::<Transform>(ball).unwrap().translation = to.extend(0.0);
+    }
+
+    #[test]
+    fn the_first_ball_in_wins_the_point() {
+        use super::*;
+
+        let (mut app, first, second) = scoring_app(MatchRules::default());
+
+        move_ball(&mut app, first, Vec2::new(-100.0, 0.0));
+        app.update();
+        assert_eq!(right_score(&mut app), 1);
+
+        // Both balls line up for the next serve where they started
+        for (ball, y) in [(first, 20.0), (second, -20.0)] {
+            assert_eq!(
+                app.world.get::<Transform>(ball).unwrap().translation,
+                Vec3::new(0.0, y, 0.0)
+            );
+            assert!(app.world.get::<Serving>(ball).is_some());
+        }
+    }
+
+    #[test]
+    fn the_last_ball_in_wins_the_point() {
+        use super::*;
+
+        let (mut app, first, second) =
+            scoring_app(MatchRules::default().with_point_ends(PointEnds::LastBall));
+        let extra = app
+            .world
+            .spawn((ball::Bundle::default().with_position(Vec2::ZERO), Extra))
+            .id();
+
+        // The first two balls in sit out the rest of the point
+        move_ball(&mut app, first, Vec2::new(-100.0, 0.0));
+        move_ball(&mut app, extra, Vec2::new(-100.0, 10.0));
+        app.update();
+        assert_eq!(right_score(&mut app), 0);
+        assert!(app.world.get::<Out>(first).is_some());
+        assert!(app.world.get::<Out>(extra).is_some());
+        assert_eq!(
+            app.world.get::<Visibility>(first),
+            Some(&Visibility::Hidden)
+        );
+        assert!(app.world.get::<Out>(second).is_none());
+
+        move_ball(&mut app, second, Vec2::new(-100.0, -10.0));
+        app.update();
+        assert_eq!(right_score(&mut app), 1);
+
+        // Everything is back in play for the next point, apart from the extra
+        // ball, which is gone
+        assert!(app.world.get::<Out>(first).is_none());
+        assert_eq!(
+            app.world.get::<Visibility>(first),
+            Some(&Visibility::Inherited)
+        );
+        assert!(app.world.get_entity(extra).is_none());
+        assert_eq!(
+            app.world
+                .query_filtered::<(), With<Serving>>()
+                .iter(&app.world)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn every_ball_in_play_moves() {
+        use super::*;
+
+        Test {
+            setup: |app| {
+                app.add_event::<collider::Event>()
+                    .add_event::<shake::Event>()
+                    .init_resource::<Deflection>()
+                    .insert_resource(steady_rally())
+                    .add_system(collide_ball);
+                app.world.spawn((
+                    ball::Bundle::default().with_velocity(Vec2::new(60.0, 0.0)),
+                    Out,
+                ));
+                app.world
+                    .spawn(ball::Bundle::default().with_velocity(Vec2::new(-60.0, 0.0)))
+                    .id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 1,
+            check: |app, ball| {
+                let step = app.world.resource::<FixedTime>().period.as_secs_f32();
+                let moved = app.world.get::<Transform>(ball).unwrap().translation.x;
+                assert!(60.0f32.mul_add(step, moved).abs() < 1e-3);
+
+                // Balls that are out sit still
+                let out = app
+                    .world
+                    .iter_entities()
+                    .find_map(|e| e.get::<Out>().and_then(|_| e.get::<Transform>()))
+                    .unwrap();
+                assert_eq!(out.translation.x, 0.0);
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn the_last_paddle_to_touch_the_ball_scores() {
+        use super::*;
+
+        let (mut app, first, _) = scoring_app(MatchRules::default());
+        app.world
+            .spawn(score_counter::Bundle::default().side(Side::Top));
+        app.world.get_mut::<LastHit>(first).unwrap().side = Some(Side::Top);
+
+        move_ball(&mut app, first, Vec2::new(-100.0, 0.0));
+        app.update();
+
+        let scores: Vec<(Side, u64)> = app
+            .world
+            .query::<&Score>()
+            .iter(&app.world)
+            .map(|score| (score.side, score.value))
+            .collect();
+        assert!(scores.contains(&(Side::Top, 1)));
+        assert!(scores.contains(&(Side::Right, 0)));
+    }
+
+    #[test]
+    fn losing_the_last_life_knocks_a_player_out() {
+        use super::*;
+
+        let rules = MatchRules::default()
+            .with_victory(Victory::Elimination)
+            .with_lives(1);
+        let (mut app, first, second) = scoring_app(rules);
+        app.world.spawn(
+            score_counter::Bundle::default()
+                .side(Side::Left)
+                .with_value(1),
+        );
+        let left = app
+            .world
+            .spawn((
+                Player::new(Side::Left, Vec2::new(-80.0, 0.0)),
+                Transform::default(),
+            ))
+            .id();
+        app.world.spawn((
+            Player::new(Side::Right, Vec2::new(80.0, 0.0)),
+            Transform::default(),
+        ));
+
+        move_ball(&mut app, first, Vec2::new(-100.0, 0.0));
+        app.update();
+
+        let lives = |app: &mut App, side| {
+            app.world
+                .query::<&Score>()
+                .iter(&app.world)
+                .find(|score| score.side == side)
+                .unwrap()
+                .value
+        };
+        assert_eq!(lives(&mut app, Side::Left), 0);
+        // Nobody gets a point for it
+        assert_eq!(lives(&mut app, Side::Right), 0);
+
+        // The left paddle is gone, and its goal is walled up
+        assert!(app.world.get_entity(left).is_none());
+        assert_eq!(
+            app.world
+                .query_filtered::<(), With<bounding_box::ScoreDetector>>()
+                .iter(&app.world)
+                .count(),
+            0
+        );
+
+        // The next serve goes to someone still playing
+        for ball in [first, second] {
+            assert_eq!(app.world.get::<Serving>(ball).unwrap().toward, Side::Right);
+        }
+    }
 }
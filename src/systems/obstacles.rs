@@ -0,0 +1,174 @@
+use bevy::prelude::{
+    BuildChildren, Commands, DespawnRecursiveExt, Entity, FixedTime, Quat, Query, Res, Transform,
+    With, Without,
+};
+
+use crate::component::{
+    ball::Ball,
+    game::Game,
+    obstacle::{Lifetime, Obstacle, Rotating, Spawner, Waypoints},
+    velocity::Velocity,
+};
+
+/// Moves obstacles along their paths and spins them. Their velocity is kept
+/// up to date so the ball can tell how hard it was hit.
+pub fn move_obstacles(
+    mut obstacle_q: Query<
+        (&mut Transform, &mut Velocity, &mut Waypoints, &Rotating),
+        With<Obstacle>,
+    >,
+    fixed_time: Res<FixedTime>,
+) {
+    let step = fixed_time.period.as_secs_f32();
+    for (mut tf, mut vel, mut waypoints, rotating) in &mut obstacle_q {
+        let from = tf.translation.truncate();
+        let speed = waypoints.speed;
+        let to = waypoints.advance(from, speed * step);
+        tf.translation = to.extend(tf.translation.z);
+        *vel = ((to - from) / step).into();
+        tf.rotate(Quat::from_rotation_z(rotating.0 * step));
+    }
+}
+
+/// Spawns obstacles from each of the game's spawners when their timers run
+/// out. An obstacle that would land on the ball waits for the next time
+/// instead.
+pub fn spawn_timed_obstacles(
+    mut commands: Commands,
+    mut spawner_q: Query<&mut Spawner>,
+    game_q: Query<Entity, With<Game>>,
+    ball_q: Query<&Transform, (With<Ball>, Without<Obstacle>)>,
+    fixed_time: Res<FixedTime>,
+) {
+    let Ok(game) = game_q.get_single() else {
+        return;
+    };
+
+    for mut spawner in &mut spawner_q {
+        if !spawner.timer.tick(fixed_time.period).just_finished() {
+            continue;
+        }
+
+        let obstacle = spawner.blueprint.bundle();
+        let on_the_ball = ball_q.iter().any(|ball_tf| {
+            obstacle
+                .hull
+                .contact(
+                    &Transform::from_translation(spawner.blueprint.position.extend(0.0)),
+                    ball_tf.translation.truncate(),
+                    ball_tf.scale.x / 2.0,
+                )
+                .is_some()
+        });
+        if on_the_ball {
+            continue;
+        }
+
+        let mut entity = commands.spawn(obstacle);
+        if let Some(lifetime) = spawner.blueprint.lifetime() {
+            entity.insert(lifetime);
+        }
+        entity.set_parent(game);
+    }
+}
+
+/// Removes obstacles whose time is up.
+pub fn expire_obstacles(
+    mut commands: Commands,
+    mut obstacle_q: Query<(Entity, &mut Lifetime)>,
+    fixed_time: Res<FixedTime>,
+) {
+    for (entity, mut lifetime) in &mut obstacle_q {
+        if lifetime.0.tick(fixed_time.period).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::{App, IntoSystemConfigs, MinimalPlugins, Parent, Vec2};
+
+    use super::*;
+    use crate::{
+        component::obstacle::{Blueprint, Shape},
+        tests::helpers::{spawn_match, still_obstacle, Match},
+    };
+
+    #[test]
+    fn obstacles_move_spawn_and_expire() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_systems((spawn_timed_obstacles, expire_obstacles, move_obstacles).chain());
+        let step = app.world.resource::<FixedTime>().period.as_secs_f32();
+
+        let Match { game, .. } = spawn_match(&mut app);
+        let sliding = app
+            .world
+            .spawn(
+                Blueprint {
+                    path: vec![Vec2::new(100.0, 0.0)],
+                    speed: 10.0 / step,
+                    ..still_obstacle(Shape::Rectangle(Vec2::ONE), Vec2::ZERO)
+                }
+                .bundle(),
+            )
+            .id();
+        let timed = Blueprint {
+            lifetime: Some(step * 3.0),
+            ..still_obstacle(Shape::Circle(5.0), Vec2::new(0.0, 50.0))
+        };
+        let spawner = Spawner::every(step * 5.0, timed);
+        app.world.spawn(spawner);
+        // Lands on the ball in the middle of the court, so it never turns up
+        let blocked = Spawner::every(step, still_obstacle(Shape::Circle(5.0), Vec2::ZERO));
+        app.world.spawn(blocked);
+
+        let timed_count = |app: &mut App| {
+            app.world
+                .query_filtered::<(), With<Lifetime>>()
+                .iter(&app.world)
+                .count()
+        };
+
+        for _ in 0..4 {
+            app.update();
+        }
+        assert_eq!(timed_count(&mut app), 0);
+        let sliding_x = |app: &App| app.world.get::<Transform>(sliding).unwrap().translation.x;
+        assert!((sliding_x(&app) - 40.0).abs() < 1e-3);
+        assert!(app
+            .world
+            .get::<Velocity>(sliding)
+            .unwrap()
+            .abs_diff_eq(Vec2::new(10.0 / step, 0.0), 1e-2));
+
+        app.update();
+        assert_eq!(timed_count(&mut app), 1);
+        let spawned = app
+            .world
+            .query_filtered::<Entity, With<Lifetime>>()
+            .single(&app.world);
+        assert_eq!(app.world.get::<Parent>(spawned).unwrap().get(), game);
+
+        // Nothing was spawned on the ball
+        assert_eq!(
+            app.world
+                .query_filtered::<(), With<Obstacle>>()
+                .iter(&app.world)
+                .count(),
+            2
+        );
+
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(timed_count(&mut app), 0);
+
+        // Turned around at the end of the path
+        for _ in 0..4 {
+            app.update();
+        }
+        assert!((sliding_x(&app) - 80.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,269 @@
+use bevy::{
+    prelude::{
+        debug, info, AssetServer, Assets, AudioSink, AudioSinkPlayback, BuildChildren, Color,
+        Commands, DespawnRecursiveExt, Entity, EventReader, Input, KeyCode, NextState, Query, Res,
+        ResMut, SpatialBundle, Sprite, SpriteBundle, State, Text2dBundle, Transform, Vec2, With,
+    },
+    text::{Text, TextAlignment, TextStyle},
+    window::WindowFocused,
+};
+
+use crate::{
+    component::{
+        controls::{Action, ActionState},
+        game::Game,
+        paddle::Side,
+        pause_menu::PauseMenu,
+    },
+    constants::{LOGICAL_VIEWPORT_HEIGHT, LOGICAL_VIEWPORT_WIDTH},
+    resources::{Bindings, MatchStats, Series},
+    states::AppState,
+};
+
+use super::MusicController;
+
+/// The key that restarts the match from the pause menu.
+pub const RESTART_KEY: KeyCode = KeyCode::R;
+
+/// The key that quits to the main menu from the pause menu.
+pub const QUIT_KEY: KeyCode = KeyCode::Q;
+
+/// The pause keys bound for every side, without repeats.
+fn pause_keys(bindings: &Bindings) -> Vec<KeyCode> {
+    let mut keys = Vec::new();
+    for side in Side::ALL {
+        let key = bindings.get(side).key(Action::Pause);
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// The prompt shown in the pause menu, naming whichever keys are bound to
+/// pause.
+fn pause_menu_label(bindings: &Bindings) -> String {
+    let resume: Vec<_> = pause_keys(bindings)
+        .iter()
+        .map(|key| format!("{key:?}"))
+        .collect();
+    format!(
+        "{} - Resume\n{:?} - Restart\n{:?} - Quit to menu",
+        resume.join("/"),
+        RESTART_KEY,
+        QUIT_KEY
+    )
+}
+
+/// Pauses the match when any player presses pause, and resumes it when pause
+/// is pressed again.
+pub fn toggle_pause(
+    actions_q: Query<&ActionState>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if !actions_q
+        .iter()
+        .any(|actions| actions.just_pressed(Action::Pause))
+    {
+        return;
+    }
+
+    match state.0 {
+        AppState::InGame => next_state.set(AppState::Paused),
+        AppState::Paused => next_state.set(AppState::InGame),
+        _ => {}
+    }
+}
+
+/// Pauses the match when the window loses focus, so that nobody concedes a
+/// point while they're looking at another window.
+pub fn pause_on_focus_loss(
+    mut ev_focus: EventReader<WindowFocused>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if ev_focus.iter().any(|ev| !ev.focused) {
+        info!("Window lost focus, pausing");
+        next_state.set(AppState::Paused);
+    }
+}
+
+/// Dims the match and shows the pause menu over it.
+pub fn setup_pause_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    bindings: Res<Bindings>,
+) {
+    info!("Now paused");
+
+    let font = asset_server.load("fonts/NotoSansMono-Regular.ttf");
+    let title_style = TextStyle {
+        font: font.clone(),
+        font_size: 60.0,
+        color: Color::WHITE,
+    };
+    let prompt_style = TextStyle {
+        font,
+        font_size: 30.0,
+        color: Color::WHITE,
+    };
+
+    commands
+        .spawn((
+            PauseMenu,
+            SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, 10.0)),
+        ))
+        .with_children(|parent| {
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(0.0, 0.0, 0.0, 0.7),
+                    custom_size: Some(Vec2::new(LOGICAL_VIEWPORT_WIDTH, LOGICAL_VIEWPORT_HEIGHT)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+
+            parent.spawn(Text2dBundle {
+                text: Text::from_section("P A U S E D", title_style)
+                    .with_alignment(TextAlignment::Center),
+                transform: Transform::from_xyz(0.0, 100.0, 1.0),
+                ..Default::default()
+            });
+
+            parent.spawn(Text2dBundle {
+                text: Text::from_section(pause_menu_label(&bindings), prompt_style)
+                    .with_alignment(TextAlignment::Center),
+                transform: Transform::from_xyz(0.0, -75.0, 1.0),
+                ..Default::default()
+            });
+        });
+}
+
+/// Restarts the match or quits to the main menu. Either way the paused match
+/// is thrown away, along with the series it was part of. A key that is also
+/// bound to pause only resumes the match.
+#[allow(clippy::too_many_arguments)]
+pub fn read_pause_menu_keypresses(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    bindings: Res<Bindings>,
+    game_query: Query<Entity, With<Game>>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    music_controller: Option<Res<MusicController>>,
+    mut series: ResMut<Series>,
+    mut stats: ResMut<MatchStats>,
+    mut state: ResMut<NextState<AppState>>,
+) {
+    let resume = pause_keys(&bindings);
+    let next = keys.get_just_pressed().find_map(|key| {
+        debug!("Key pressed: {:?}", key);
+        match *key {
+            key if resume.contains(&key) => None,
+            RESTART_KEY => Some(AppState::InGame),
+            QUIT_KEY => Some(AppState::MainMenu),
+            _ => None,
+        }
+    });
+    let Some(next) = next else {
+        return;
+    };
+
+    for ent in &game_query {
+        commands.entity(ent).despawn_recursive();
+    }
+    if let Some(sink) = music_controller.and_then(|music| audio_sinks.get(&music.0)) {
+        sink.stop();
+    }
+    series.reset();
+    stats.reset();
+    state.set(next);
+}
+
+/// Removes the pause menu.
+pub fn teardown_pause_menu(query: Query<Entity, With<PauseMenu>>, mut commands: Commands) {
+    info!("Now leaving pause menu");
+
+    let ent = query.single();
+    commands.entity(ent).despawn_recursive();
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::IntoSystemConfigs;
+
+    use super::*;
+    use crate::{
+        component::Bundle,
+        systems::keyboard_actions,
+        tests::helpers::{default_setup_graphics, Test},
+    };
+
+    #[test]
+    fn test_pause_menu_names_the_bound_pause_keys() {
+        let mut bindings = Bindings::default();
+        assert_eq!(
+            pause_menu_label(&bindings),
+            "Escape - Resume\nR - Restart\nQ - Quit to menu"
+        );
+
+        bindings
+            .bind(Side::Left, Action::Pause, KeyCode::P)
+            .unwrap();
+        assert_eq!(
+            pause_menu_label(&bindings),
+            "P/Escape - Resume\nR - Restart\nQ - Quit to menu"
+        );
+    }
+
+    #[test]
+    fn escape_pauses_the_match() {
+        Test {
+            setup: |app| {
+                app.add_state::<AppState>()
+                    .insert_resource(State(AppState::InGame))
+                    .init_resource::<Input<KeyCode>>()
+                    .add_systems((keyboard_actions, toggle_pause).chain());
+                app.world
+                    .resource_mut::<Input<KeyCode>>()
+                    .press(KeyCode::Escape);
+                app.world.spawn(Bundle::left_player()).id()
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 1,
+            check: |app, _| {
+                assert_eq!(
+                    app.world.resource::<NextState<AppState>>().0,
+                    Some(AppState::Paused)
+                );
+            },
+        }
+        .run();
+    }
+
+    #[test]
+    fn losing_focus_pauses_the_match() {
+        Test {
+            setup: |app| {
+                app.add_state::<AppState>()
+                    .insert_resource(State(AppState::InGame))
+                    .add_event::<WindowFocused>()
+                    .add_system(pause_on_focus_loss);
+                let window = app.world.spawn_empty().id();
+                app.world.send_event(WindowFocused {
+                    window,
+                    focused: false,
+                });
+                window
+            },
+            setup_graphics: default_setup_graphics,
+            frames: 1,
+            check: |app, _| {
+                assert_eq!(
+                    app.world.resource::<NextState<AppState>>().0,
+                    Some(AppState::Paused)
+                );
+            },
+        }
+        .run();
+    }
+}
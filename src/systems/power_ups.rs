@@ -0,0 +1,614 @@
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::{
+        AssetServer, BuildChildren, Changed, Commands, DespawnRecursiveExt, Entity, FixedTime,
+        Query, Res, Transform, Vec2, With, Without,
+    },
+    text::{Text, Text2dBundle, TextAlignment, TextStyle},
+    time::{Timer, TimerMode},
+    utils::HashMap,
+};
+
+use crate::{
+    component::{
+        ball::{self, Ball, Extra, LastHit, Out, SpeedFactor, Stuck},
+        bounding_box::is_inside_bounds,
+        controls::{Action, ActionState},
+        game::Game,
+        paddle::{Player, Side},
+        power_up::{self, Effect, Kind, Original, PowerUp, Reversed, Spawner, Sticky, Target},
+        score::Score,
+        velocity::Velocity,
+    },
+    constants::{
+        ENLARGE_FACTOR, MULTIBALL_SPLIT_ANGLE, SHRINK_FACTOR, SLOW_DOWN_FACTOR, SPEED_UP_FACTOR,
+        STICKY_HOLD_TIME,
+    },
+    resources::PowerUps,
+};
+
+/// How far below a player's score their first effect icon is shown.
+const ICON_OFFSET: f32 = 40.0;
+/// How far apart effect icons are stacked.
+const ICON_SPACING: f32 = 22.0;
+
+/// The paddles and balls that power-up effects change.
+#[allow(clippy::type_complexity)]
+#[derive(SystemParam)]
+pub struct EffectTargets<'w, 's> {
+    commands: Commands<'w, 's>,
+    paddles: Query<'w, 's, (Entity, &'static Player, &'static mut Transform), Without<Ball>>,
+    balls: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static mut SpeedFactor,
+            &'static Transform,
+            &'static Velocity,
+            &'static LastHit,
+            Option<&'static Out>,
+        ),
+        With<Ball>,
+    >,
+}
+
+impl<'w, 's> EffectTargets<'w, 's> {
+    /// Everything an effect collected by `owner` would change.
+    fn find(&self, target: Target, owner: Side) -> Vec<Entity> {
+        let paddles_where = |on_side: &dyn Fn(Side) -> bool| {
+            self.paddles
+                .iter()
+                .filter(|(_, player, _)| on_side(player.side))
+                .map(|(paddle, _, _)| paddle)
+                .collect()
+        };
+        match target {
+            Target::OwnPaddles => paddles_where(&|side| side == owner),
+            Target::OpponentPaddles => paddles_where(&|side| side != owner),
+            Target::Balls => self.balls.iter().map(|(ball, ..)| ball).collect(),
+        }
+    }
+
+    /// Puts an effect on something, and returns what it was like before.
+    fn apply(&mut self, kind: Kind, target: Entity) -> Original {
+        match kind {
+            Kind::Enlarge => self.resize(target, ENLARGE_FACTOR),
+            Kind::Shrink => self.resize(target, SHRINK_FACTOR),
+            Kind::SpeedUp => self.pace(target, SPEED_UP_FACTOR),
+            Kind::SlowDown => self.pace(target, SLOW_DOWN_FACTOR),
+            Kind::Sticky => {
+                self.commands.entity(target).insert(Sticky);
+                Original::Nothing
+            }
+            Kind::Reverse => {
+                self.commands.entity(target).insert(Reversed);
+                Original::Nothing
+            }
+            // Puts new balls in play instead of changing anything, see
+            // `split_balls`
+            Kind::Multiball => Original::Nothing,
+        }
+    }
+
+    /// Scales a paddle's length, whichever way it lies.
+    fn resize(&mut self, paddle: Entity, factor: f32) -> Original {
+        let Ok((_, player, mut tf)) = self.paddles.get_mut(paddle) else {
+            return Original::Nothing;
+        };
+        let length = paddle_length(&mut tf, player.side);
+        let original = *length;
+        *length *= factor;
+        Original::Length(original)
+    }
+
+    /// Scales a ball's pace.
+    fn pace(&mut self, ball: Entity, factor: f32) -> Original {
+        let Ok((_, mut speed, ..)) = self.balls.get_mut(ball) else {
+            return Original::Nothing;
+        };
+        let original = **speed;
+        **speed *= factor;
+        Original::SpeedFactor(original)
+    }
+
+    /// Splits another ball off each moving ball in play, heading off at an
+    /// angle to it. The new balls belong to `game`.
+    fn split_balls(&mut self, game: Entity) {
+        let rotation = Vec2::from_angle(MULTIBALL_SPLIT_ANGLE);
+        for (_, speed, tf, vel, last_hit, out) in &self.balls {
+            if out.is_some() || **vel == Vec2::ZERO {
+                continue;
+            }
+            let mut ball = ball::Bundle::default().with_velocity(rotation.rotate(**vel));
+            ball.speed_factor = *speed;
+            ball.last_hit = *last_hit;
+            self.commands
+                .spawn((ball, Extra))
+                .insert(*tf)
+                .set_parent(game);
+        }
+    }
+
+    /// Takes an effect off something, putting back what it was like before.
+    /// Anything that has gone since is skipped.
+    fn restore(&mut self, kind: Kind, target: Entity, original: Original) {
+        match original {
+            Original::Length(length) => {
+                if let Ok((_, player, mut tf)) = self.paddles.get_mut(target) {
+                    *paddle_length(&mut tf, player.side) = length;
+                }
+            }
+            Original::SpeedFactor(factor) => {
+                if let Ok((_, mut speed, ..)) = self.balls.get_mut(target) {
+                    **speed = factor;
+                }
+            }
+            Original::Nothing => {
+                let Some(mut entity) = self.commands.get_entity(target) else {
+                    return;
+                };
+                match kind {
+                    Kind::Sticky => {
+                        entity.remove::<Sticky>();
+                    }
+                    Kind::Reverse => {
+                        entity.remove::<Reversed>();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// The scale along a paddle's face, which runs across its side of the court.
+const fn paddle_length(tf: &mut Transform, side: Side) -> &mut f32 {
+    if side.moves_vertically() {
+        &mut tf.scale.y
+    } else {
+        &mut tf.scale.x
+    }
+}
+
+/// Puts a random power-up somewhere in the arena's power-up area every so
+/// often, as long as there aren't already too many waiting on the court.
+pub fn spawn_power_ups(
+    mut commands: Commands,
+    mut spawner_q: Query<&mut Spawner>,
+    power_up_q: Query<(), With<PowerUp>>,
+    game_q: Query<Entity, With<Game>>,
+    power_ups: Res<PowerUps>,
+    fixed_time: Res<FixedTime>,
+) {
+    let Ok(game) = game_q.get_single() else {
+        return;
+    };
+
+    for mut spawner in &mut spawner_q {
+        if !spawner.timer.tick(fixed_time.period).just_finished()
+            || !power_ups.enabled
+            || power_up_q.iter().count() >= power_ups.max_on_court
+        {
+            continue;
+        }
+        let Some(entry) = power_ups.pick(rand::random()) else {
+            continue;
+        };
+
+        let spread = Vec2::new(rand::random::<f32>() - 0.5, rand::random::<f32>() - 0.5);
+        let position = spawner.area.position + spread * spawner.area.size;
+        commands
+            .spawn(power_up::Bundle::new(
+                entry.kind,
+                position,
+                power_ups.lifetime,
+            ))
+            .set_parent(game);
+    }
+}
+
+/// Removes power-ups nobody collected in time.
+pub fn expire_power_ups(
+    mut commands: Commands,
+    mut power_up_q: Query<(Entity, &mut PowerUp)>,
+    fixed_time: Res<FixedTime>,
+) {
+    for (entity, mut power_up) in &mut power_up_q {
+        if power_up.expires.tick(fixed_time.period).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Gives a power-up to whoever last hit the ball when the ball passes through
+/// it. A ball nobody has hit yet this rally passes straight through. Multiball
+/// takes effect straight away, and every other power-up lasts a while.
+///
+/// The effect replaces any older one in the same slot on the same things, so
+/// that effects always wear off back to how things were before either of
+/// them.
+#[allow(clippy::type_complexity)]
+pub fn collect_power_ups(
+    mut commands: Commands,
+    power_up_q: Query<(Entity, &Transform, &PowerUp), (Without<Ball>, Without<Player>)>,
+    ball_q: Query<(&Transform, &LastHit), (With<Ball>, Without<Player>)>,
+    mut effect_q: Query<(Entity, &mut Effect)>,
+    mut targets: EffectTargets,
+    game_q: Query<Entity, With<Game>>,
+    power_ups: Res<PowerUps>,
+) {
+    let Ok(game) = game_q.get_single() else {
+        return;
+    };
+
+    for (power_up, power_up_tf, PowerUp { kind, .. }) in &power_up_q {
+        let owner = ball_q.iter().find_map(|(ball_tf, last_hit)| {
+            last_hit
+                .side
+                .filter(|_| is_inside_bounds(power_up_tf, ball_tf))
+        });
+        let Some(owner) = owner else {
+            continue;
+        };
+        commands.entity(power_up).despawn_recursive();
+
+        let kind = *kind;
+        if kind == Kind::Multiball {
+            // The new balls stay in play until they score or the point ends,
+            // so there is nothing to wear off
+            targets.split_balls(game);
+            continue;
+        }
+
+        let affected = targets.find(kind.target(), owner);
+        for (effect, mut older) in &mut effect_q {
+            if older.kind.slot() != kind.slot() {
+                continue;
+            }
+            let older_kind = older.kind;
+            older.targets.retain(|&(target, original)| {
+                let replaced = affected.contains(&target);
+                if replaced {
+                    targets.restore(older_kind, target, original);
+                }
+                !replaced
+            });
+            if older.targets.is_empty() {
+                commands.entity(effect).despawn_recursive();
+            }
+        }
+
+        let changed = affected
+            .into_iter()
+            .map(|target| (target, targets.apply(kind, target)))
+            .collect();
+        let duration = power_ups.get(kind).map_or(0.0, |entry| entry.duration);
+        commands
+            .spawn(Effect {
+                kind,
+                owner,
+                timer: Timer::from_seconds(duration, TimerMode::Once),
+                targets: changed,
+            })
+            .set_parent(game);
+    }
+}
+
+/// Counts down each effect, putting things back the way they were when it
+/// wears off.
+pub fn expire_effects(
+    mut commands: Commands,
+    mut effect_q: Query<(Entity, &mut Effect)>,
+    mut targets: EffectTargets,
+    fixed_time: Res<FixedTime>,
+) {
+    for (entity, mut effect) in &mut effect_q {
+        if !effect.timer.tick(fixed_time.period).finished() {
+            continue;
+        }
+        let kind = effect.kind;
+        for (target, original) in effect.targets.drain(..) {
+            targets.restore(kind, target, original);
+        }
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Catches the ball on a sticky paddle as soon as the paddle hits it.
+#[allow(clippy::type_complexity)]
+pub fn stick_ball(
+    mut commands: Commands,
+    mut ball_q: Query<
+        (Entity, &Transform, &mut Velocity, &LastHit),
+        (With<Ball>, Without<Stuck>, Changed<LastHit>),
+    >,
+    paddle_q: Query<&Transform, (With<Sticky>, Without<Ball>)>,
+) {
+    for (ball, ball_tf, mut vel, last_hit) in &mut ball_q {
+        let Some((paddle, paddle_tf)) = last_hit
+            .paddle
+            .and_then(|paddle| paddle_q.get(paddle).ok().map(|tf| (paddle, tf)))
+        else {
+            continue;
+        };
+        commands.entity(ball).insert(Stuck {
+            paddle,
+            offset: (ball_tf.translation - paddle_tf.translation).truncate(),
+            velocity: **vel,
+            timer: Timer::from_seconds(STICKY_HOLD_TIME, TimerMode::Once),
+            served: false,
+        });
+        *vel = Velocity::default();
+    }
+}
+
+/// Remembers when the player holding a ball presses serve, so that the next
+/// fixed step lets go of it however many frames apart the steps are.
+pub fn latch_serves(mut ball_q: Query<&mut Stuck>, paddle_q: Query<&ActionState>) {
+    for mut stuck in &mut ball_q {
+        if paddle_q
+            .get(stuck.paddle)
+            .is_ok_and(|actions| actions.just_pressed(Action::Serve))
+        {
+            stuck.served = true;
+        }
+    }
+}
+
+/// Carries a caught ball along with its paddle, and lets it go when the
+/// paddle's player serves, when the paddle has held it for long enough, or
+/// when the paddle stops being sticky.
+#[allow(clippy::type_complexity)]
+pub fn hold_stuck_ball(
+    mut commands: Commands,
+    mut ball_q: Query<(Entity, &mut Transform, &mut Velocity, &mut Stuck), With<Ball>>,
+    paddle_q: Query<&Transform, (With<Sticky>, Without<Ball>)>,
+    fixed_time: Res<FixedTime>,
+) {
+    for (ball, mut ball_tf, mut vel, mut stuck) in &mut ball_q {
+        let held_too_long = stuck.timer.tick(fixed_time.period).finished();
+        match paddle_q.get(stuck.paddle) {
+            Ok(paddle_tf) if !held_too_long && !stuck.served => {
+                ball_tf.translation = paddle_tf.translation + stuck.offset.extend(0.0);
+            }
+            _ => {
+                *vel = stuck.velocity.into();
+                commands.entity(ball).remove::<Stuck>();
+            }
+        }
+    }
+}
+
+/// Shows each effect under the score of the side that collected it, with how
+/// long it has left.
+pub fn show_effect_icons(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut effect_q: Query<(Entity, &Effect, Option<&mut Text>, Option<&mut Transform>)>,
+    score_q: Query<(&Transform, &Score), Without<Effect>>,
+) {
+    let mut shown = HashMap::new();
+    for (entity, effect, text, tf) in &mut effect_q {
+        let label = format!("{} {}", effect.kind.label(), effect.seconds_left());
+        let row = shown.entry(effect.owner).or_insert(0);
+        let below_score = score_q
+            .iter()
+            .find(|(_, score)| score.side == effect.owner)
+            .map_or(Vec2::ZERO, |(tf, _)| tf.translation.truncate());
+        let position = below_score - Vec2::new(0.0, ICON_SPACING.mul_add(*row as f32, ICON_OFFSET));
+        *row += 1;
+
+        match (text, tf) {
+            (Some(mut text), Some(mut tf)) => {
+                if text.sections[0].value != label {
+                    text.sections[0].value = label;
+                }
+                tf.translation = position.extend(1.0);
+            }
+            _ => {
+                let style = TextStyle {
+                    font: asset_server.load("fonts/NotoSansMono-Regular.ttf"),
+                    font_size: 20.0,
+                    color: effect.kind.color(),
+                };
+                commands.entity(entity).insert(Text2dBundle {
+                    text: Text::from_section(label, style).with_alignment(TextAlignment::Center),
+                    transform: Transform::from_translation(position.extend(1.0)),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::{
+        App, CoreSchedule, CoreSet, IntoSystemAppConfig, IntoSystemConfigs, Local, MinimalPlugins,
+        Parent, Vec3,
+    };
+
+    use super::*;
+    use crate::{
+        constants::TIME_STEP,
+        tests::helpers::{run_at_fps, spawn_match, Match},
+    };
+
+    #[test]
+    fn power_ups_take_effect_and_wear_off() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_systems((collect_power_ups, expire_effects).chain());
+        let step = app.world.resource::<FixedTime>().period.as_secs_f32();
+        app.insert_resource(
+            PowerUps::empty()
+                .register(Kind::Enlarge, 1.0, step * 4.0)
+                .register(Kind::Shrink, 1.0, step * 2.0),
+        );
+
+        let Match {
+            left, right, ball, ..
+        } = spawn_match(&mut app);
+        let height = |app: &App, paddle| app.world.get::<Transform>(paddle).unwrap().scale.y;
+        let original = height(&app, left);
+
+        // Nobody has hit the ball yet, so it passes through
+        app.world
+            .spawn(power_up::Bundle::new(Kind::Enlarge, Vec2::ZERO, 10.0));
+        app.update();
+        assert_eq!(height(&app, left), original);
+
+        app.world.entity_mut(ball).insert(LastHit {
+            side: Some(Side::Left),
+            paddle: Some(left),
+        });
+        app.update();
+        assert_eq!(height(&app, left), original * ENLARGE_FACTOR);
+        assert_eq!(height(&app, right), original);
+
+        // The right player shrinks the left paddle, which replaces the
+        // enlarge instead of piling on top of it
+        app.world.entity_mut(ball).insert(LastHit {
+            side: Some(Side::Right),
+            paddle: Some(right),
+        });
+        app.world
+            .spawn(power_up::Bundle::new(Kind::Shrink, Vec2::ZERO, 10.0));
+        app.update();
+        assert_eq!(height(&app, left), original * SHRINK_FACTOR);
+        let effects = app
+            .world
+            .query::<&Effect>()
+            .iter(&app.world)
+            .map(|effect| (effect.kind, effect.owner))
+            .collect::<Vec<_>>();
+        assert_eq!(effects, vec![(Kind::Shrink, Side::Right)]);
+
+        for _ in 0..2 {
+            app.update();
+        }
+        assert_eq!(height(&app, left), original);
+        assert_eq!(app.world.query::<&Effect>().iter(&app.world).count(), 0);
+    }
+
+    #[test]
+    fn sticky_paddles_catch_the_ball() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_systems((latch_serves, hold_stuck_ball, stick_ball).chain());
+
+        let Match {
+            left: paddle, ball, ..
+        } = spawn_match(&mut app);
+        app.world.entity_mut(paddle).insert(Sticky);
+        app.world.get_mut::<Transform>(ball).unwrap().translation = Vec3::new(10.0, 5.0, 0.0);
+        *app.world.get_mut::<Velocity>(ball).unwrap() = Vec2::new(300.0, 0.0).into();
+        app.world.entity_mut(ball).insert(LastHit {
+            side: Some(Side::Left),
+            paddle: Some(paddle),
+        });
+        app.update();
+        assert!(app.world.get::<Stuck>(ball).is_some());
+        assert_eq!(**app.world.get::<Velocity>(ball).unwrap(), Vec2::ZERO);
+
+        // The ball is carried along with the paddle
+        app.world
+            .get_mut::<Transform>(paddle)
+            .unwrap()
+            .translation
+            .y = 50.0;
+        app.update();
+        assert_eq!(
+            app.world.get::<Transform>(ball).unwrap().translation,
+            Vec3::new(10.0, 55.0, 0.0)
+        );
+
+        // Serving lets go of it again
+        app.world
+            .get_mut::<ActionState>(paddle)
+            .unwrap()
+            .press(Action::Serve);
+        app.update();
+        assert!(app.world.get::<Stuck>(ball).is_none());
+        assert_eq!(
+            **app.world.get::<Velocity>(ball).unwrap(),
+            Vec2::new(300.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn serving_lets_go_of_a_stuck_ball_at_a_high_frame_rate() {
+        /// Presses serve on one frame only. At 144 fps, frame 40 falls
+        /// between two fixed steps.
+        fn press_serve_on_frame_40(mut frame: Local<u32>, mut actions_q: Query<&mut ActionState>) {
+            for mut actions in &mut actions_q {
+                actions.begin_frame();
+                actions.clear();
+                if *frame == 40 {
+                    actions.press(Action::Serve);
+                }
+            }
+            *frame += 1;
+        }
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(FixedTime::new_from_secs(TIME_STEP))
+            .add_systems(
+                (press_serve_on_frame_40, latch_serves)
+                    .chain()
+                    .in_base_set(CoreSet::PreUpdate),
+            )
+            .add_system(hold_stuck_ball.in_schedule(CoreSchedule::FixedUpdate));
+
+        let Match {
+            left: paddle, ball, ..
+        } = spawn_match(&mut app);
+        app.world.entity_mut(paddle).insert(Sticky);
+        app.world.entity_mut(ball).insert(Stuck {
+            paddle,
+            offset: Vec2::new(10.0, 0.0),
+            velocity: Vec2::new(300.0, 0.0),
+            timer: Timer::from_seconds(STICKY_HOLD_TIME, TimerMode::Once),
+            served: false,
+        });
+
+        // Well before the paddle would let go on its own
+        run_at_fps(&mut app, 144, 1);
+        assert!(app.world.get::<Stuck>(ball).is_none());
+        assert_eq!(
+            **app.world.get::<Velocity>(ball).unwrap(),
+            Vec2::new(300.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn multiball_splits_the_ball() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<PowerUps>()
+            .add_system(collect_power_ups);
+
+        let Match { game, ball, .. } = spawn_match(&mut app);
+        *app.world.get_mut::<Velocity>(ball).unwrap() = Vec2::new(100.0, 0.0).into();
+        app.world.entity_mut(ball).insert(LastHit {
+            side: Some(Side::Left),
+            paddle: None,
+        });
+        app.world
+            .spawn(power_up::Bundle::new(Kind::Multiball, Vec2::ZERO, 10.0));
+        app.update();
+
+        let extra = app
+            .world
+            .query_filtered::<(&Velocity, &LastHit, &Parent), With<Extra>>()
+            .single(&app.world);
+        assert!((extra.0.length() - 100.0).abs() < 1e-3);
+        assert!(extra.0.y > 0.0);
+        assert_eq!(extra.1.side, Some(Side::Left));
+        assert_eq!(extra.2.get(), game);
+        // Nothing to wear off
+        assert_eq!(app.world.query::<&Effect>().iter(&app.world).count(), 0);
+    }
+}
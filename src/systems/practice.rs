@@ -0,0 +1,169 @@
+use bevy::{
+    ecs::schedule::SystemConfigs,
+    prelude::{
+        info, EventReader, FixedTime, IntoSystemConfigs, NextState, Query, Res, ResMut, Vec2, With,
+    },
+    text::Text,
+};
+
+use crate::{
+    component::{ball::Ball, score::Score, velocity::Velocity},
+    constants::PRACTICE_SPEED_UP,
+    events::score,
+    resources::{Opponent, Practice, RallySpeed},
+    states::AppState,
+};
+
+use super::{collide_ball, detect_score};
+
+/// The systems that keep a wall practice run going. The ball picks up pace
+/// and the rally is counted as it moves, before anything can score. They do
+/// nothing unless the player is up against the wall; `end_practice_run` takes
+/// over once the ball gets past them.
+pub fn practice_systems() -> SystemConfigs {
+    (speed_up_ball, count_rally)
+        .chain()
+        .after(collide_ball)
+        .before(detect_score)
+}
+
+/// Speeds the ball up a little every step while practising against the wall,
+/// up to the rally's speed limit, so the longer a run goes the harder it gets.
+pub fn speed_up_ball(
+    opponent: Res<Opponent>,
+    fixed_time: Res<FixedTime>,
+    mut rally: ResMut<RallySpeed>,
+    mut ball_q: Query<&mut Velocity, With<Ball>>,
+) {
+    if *opponent != Opponent::Wall {
+        return;
+    }
+
+    let factor = PRACTICE_SPEED_UP.mul_add(fixed_time.period.as_secs_f32(), 1.0);
+    for mut vel in &mut ball_q {
+        if **vel == Vec2::ZERO {
+            continue;
+        }
+        let speed = (vel.length() * factor).min(rally.max_speed);
+        *vel = (vel.normalize() * speed).into();
+        rally.speed = speed;
+    }
+}
+
+/// Shows how many times the ball has been hit this run as the player's score.
+pub fn count_rally(
+    opponent: Res<Opponent>,
+    rally: Res<RallySpeed>,
+    mut score_q: Query<(&mut Score, &mut Text)>,
+) {
+    if *opponent != Opponent::Wall {
+        return;
+    }
+
+    let hits = u64::from(rally.hits);
+    for (mut score, mut text) in &mut score_q {
+        if score.value != hits {
+            score.value = hits;
+            text.sections[0].value = score.to_string();
+        }
+    }
+}
+
+/// Ends the run the first time the ball gets past the player, keeping the
+/// rally as the personal best if it beats it.
+pub fn end_practice_run(
+    mut ev_score: EventReader<score::Event>,
+    opponent: Res<Opponent>,
+    score_q: Query<&Score>,
+    mut practice: ResMut<Practice>,
+    mut state: ResMut<NextState<AppState>>,
+) {
+    if ev_score.iter().next().is_none() {
+        return;
+    }
+    ev_score.clear();
+    if *opponent != Opponent::Wall {
+        return;
+    }
+
+    let hits = score_q.iter().map(|score| score.value).max().unwrap_or(0);
+    practice.finish(u32::try_from(hits).unwrap_or(u32::MAX));
+    info!("Practice run over after {} hits", practice.last);
+    state.set(AppState::GameOver);
+}
+
+/// Saves the personal best if the last run beat it.
+pub fn save_personal_best(practice: Res<Practice>) {
+    if practice.new_best {
+        info!("Saving personal best");
+        practice.save();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::{App, MinimalPlugins, State};
+
+    use super::*;
+    use crate::component::{ball, paddle::Side, score as score_counter};
+
+    #[test]
+    fn wall_practice_counts_the_rally_until_the_first_miss() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_state::<AppState>()
+            .add_event::<score::Event>()
+            .insert_resource(Opponent::Wall)
+            .init_resource::<RallySpeed>()
+            .init_resource::<Practice>()
+            .add_systems((count_rally, end_practice_run).chain());
+        app.world
+            .spawn(score_counter::Bundle::default().side(Side::Left));
+
+        app.world.resource_mut::<RallySpeed>().hits = 7;
+        app.update();
+        let score = app.world.query::<&Score>().single(&app.world).value;
+        assert_eq!(score, 7);
+        assert_eq!(
+            app.world.resource::<State<AppState>>().0,
+            AppState::MainMenu
+        );
+
+        app.world.send_event(score::Event::new(Side::Right));
+        app.update();
+        app.update();
+        let practice = *app.world.resource::<Practice>();
+        assert_eq!((practice.last, practice.best), (7, 7));
+        assert!(practice.new_best);
+        assert_eq!(
+            app.world.resource::<State<AppState>>().0,
+            AppState::GameOver
+        );
+    }
+
+    #[test]
+    fn wall_practice_speeds_the_ball_up() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(Opponent::Wall)
+            .insert_resource(RallySpeed::default().with_max_speed(110.0))
+            .add_system(speed_up_ball);
+        let ball = app
+            .world
+            .spawn(ball::Bundle::default().with_velocity(Vec2::new(100.0, 0.0)))
+            .id();
+
+        app.update();
+        let speed = app.world.get::<Velocity>(ball).unwrap().length();
+        assert!(speed > 100.0);
+        assert_eq!(app.world.resource::<RallySpeed>().speed, speed);
+
+        // Up to the rally's speed limit
+        for _ in 0..1000 {
+            app.update();
+        }
+        let vel = **app.world.get::<Velocity>(ball).unwrap();
+        assert!((vel.length() - 110.0).abs() < 1e-3);
+        assert_eq!(vel.y, 0.0);
+    }
+}
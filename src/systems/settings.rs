@@ -0,0 +1,58 @@
+use bevy::{
+    prelude::{info, Commands, DetectChanges, Query, Res, ResMut, With},
+    window::{PrimaryWindow, Window},
+};
+
+use crate::{
+    plugins::window_scaling_2d::resources::AspectRatio,
+    resources::{MatchRules, Multiball, PowerUps, RallySpeed, Serve, Settings},
+};
+
+/// Puts the player's settings into effect whenever they change. Paddle and
+/// audio settings are read as the paddles are spawned and sounds are played.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_settings(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut rules: ResMut<MatchRules>,
+    mut rally: ResMut<RallySpeed>,
+    mut serve: ResMut<Serve>,
+    mut power_ups: ResMut<PowerUps>,
+    mut multiball: ResMut<Multiball>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    *rules = settings.rules;
+    *rally = RallySpeed::default()
+        .with_serve_speed(settings.ball.serve_speed)
+        .with_hit_multiplier(settings.ball.speed_up_per_hit)
+        .with_max_speed(settings.ball.max_speed);
+    serve.countdown = settings.ball.serve_countdown;
+    power_ups.enabled = settings.power_ups.enabled;
+    power_ups.every = settings.power_ups.every;
+    *multiball = Multiball {
+        enabled: settings.multiball.enabled,
+        balls: settings.multiball.balls,
+    };
+
+    match settings.video.aspect_ratio {
+        Some(ratio) => commands.insert_resource(AspectRatio(ratio)),
+        None => commands.remove_resource::<AspectRatio>(),
+    }
+    for mut window in &mut windows {
+        window.mode = settings.video.window_mode;
+    }
+}
+
+/// Saves the settings whenever they're changed in the game. Loading them
+/// doesn't count, so the file is only written once the player changes
+/// something.
+pub fn save_settings(settings: Res<Settings>) {
+    if settings.is_changed() && !settings.is_added() {
+        info!("Saving settings");
+        settings.save();
+    }
+}
@@ -1,7 +1,17 @@
 use bevy::prelude::*;
 use bevy::render::camera::ScalingMode;
+use bevy::time::TimeUpdateStrategy;
+use bevy::utils::Duration;
 // use bevy_inspector_egui::WorldInspectorPlugin;
 
+use crate::component::{
+    ball,
+    game::Game,
+    obstacle::{Blueprint, Shape},
+    Bundle,
+};
+use crate::resources::RallySpeed;
+
 use std::thread;
 
 pub fn on_main_thread() -> bool {
@@ -41,6 +51,59 @@ pub fn default_setup_graphics(app: &mut App) {
     });
 }
 
+/// Updates the app as if it were rendering at `fps` frames per second for
+/// `seconds` of game time. The clock is advanced manually, so the outcome does
+/// not depend on how fast the machine running the test is.
+pub fn run_at_fps(app: &mut App, fps: u32, seconds: u32) {
+    let startup = app.world.resource::<Time>().startup();
+    for frame in 0..=fps * seconds {
+        let elapsed = Duration::from_secs(frame.into()) / fps;
+        app.insert_resource(TimeUpdateStrategy::ManualInstant(startup + elapsed));
+        app.update();
+    }
+}
+
+/// The entities `spawn_match` puts on the court.
+pub struct Match {
+    pub game: Entity,
+    pub left: Entity,
+    pub right: Entity,
+    pub ball: Entity,
+}
+
+/// Spawns what a match is played with, for tests of the systems that act on
+/// it: the `Game`, a paddle for the left and the right player, and a ball
+/// sitting still in the middle of the court.
+pub fn spawn_match(app: &mut App) -> Match {
+    Match {
+        game: app.world.spawn(Game).id(),
+        left: app.world.spawn(Bundle::left_player()).id(),
+        right: app.world.spawn(Bundle::right_player()).id(),
+        ball: app.world.spawn(ball::Bundle::default()).id(),
+    }
+}
+
+/// An obstacle that sits still, for tests that are about something else.
+pub const fn still_obstacle(shape: Shape, position: Vec2) -> Blueprint {
+    Blueprint {
+        shape,
+        position,
+        rotation: 0.0,
+        spin: 0.0,
+        path: Vec::new(),
+        speed: 0.0,
+        lifetime: None,
+    }
+}
+
+/// A rally that never changes the ball's speed, for tests that are about
+/// something else.
+pub fn steady_rally() -> RallySpeed {
+    RallySpeed::default()
+        .with_hit_multiplier(1.0)
+        .with_max_speed(f32::INFINITY)
+}
+
 fn app() -> (App, bool) {
     let mut app = App::new();
     let on_main_thread = if on_main_thread() {